@@ -0,0 +1,128 @@
+use chrono::{
+    DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc,
+};
+
+/// Diary day `date` counts toward in the current local zone, days start at `day_start_hour`
+/// so late night meals can count toward the previous day.
+pub fn diary_date(date: DateTime<Utc>, day_start_hour: u32) -> NaiveDate {
+    local_diary_date(DateTime::<Local>::from(date).naive_local(), day_start_hour)
+}
+
+/// Diary day of a wall clock time, in whichever zone it was recorded.
+pub fn local_diary_date(local: NaiveDateTime, day_start_hour: u32) -> NaiveDate {
+    (local - Duration::hours(day_start_hour as i64)).date()
+}
+
+/// Diary day of the current moment.
+pub fn diary_today(day_start_hour: u32) -> NaiveDate {
+    diary_date(Utc::now(), day_start_hour)
+}
+
+/// Wall clock time of `date` in the zone it was recorded in, given in seconds east of UTC,
+/// falling back to the current local zone.
+pub fn recorded_local_time(date: DateTime<Utc>, utc_offset: Option<i32>) -> DateTime<FixedOffset> {
+    let offset = utc_offset
+        .and_then(FixedOffset::east_opt)
+        .unwrap_or_else(|| local_offset(date));
+    date.with_timezone(&offset)
+}
+
+/// Offset of the current local zone at `date`, which differs between summer and winter time.
+pub fn local_offset(date: DateTime<Utc>) -> FixedOffset {
+    Local.offset_from_utc_datetime(&date.naive_utc()).fix()
+}
+
+/// Something logged at a moment, along with the zone it was logged in.
+pub trait Recorded {
+    fn date(&self) -> DateTime<Utc>;
+
+    /// Seconds east of UTC where the entry was logged, missing on entries from older versions.
+    fn utc_offset(&self) -> Option<i32>;
+
+    fn utc_offset_mut(&mut self) -> &mut Option<i32>;
+
+    /// Wall clock time in the zone the entry was logged in.
+    fn local_time(&self) -> DateTime<FixedOffset> {
+        recorded_local_time(self.date(), self.utc_offset())
+    }
+
+    fn diary_date(&self, day_start_hour: u32) -> NaiveDate {
+        local_diary_date(self.local_time().naive_local(), day_start_hour)
+    }
+}
+
+/// Sorts entries by date and fills in the zone of entries logged before zones were recorded,
+/// assuming the current local zone. Returns whether any entry was filled in and needs saving.
+pub fn sort_and_migrate<T: Recorded>(entries: &mut [T]) -> bool {
+    entries.sort_by_key(|entry| entry.date());
+    let mut migrated = false;
+    for entry in entries.iter_mut() {
+        let date = entry.date();
+        let utc_offset = entry.utc_offset_mut();
+        if utc_offset.is_none() {
+            *utc_offset = Some(local_offset(date).local_minus_utc());
+            migrated = true;
+        }
+    }
+    migrated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn day(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn late_night_counts_toward_the_previous_day() {
+        assert_eq!(
+            local_diary_date(at("2024-03-10 01:00"), 4),
+            day("2024-03-09")
+        );
+        assert_eq!(
+            local_diary_date(at("2024-03-10 04:00"), 4),
+            day("2024-03-10")
+        );
+        assert_eq!(
+            local_diary_date(at("2024-03-10 01:00"), 0),
+            day("2024-03-10")
+        );
+    }
+
+    #[test]
+    fn recorded_offset_wins_over_the_local_zone() {
+        let date = Utc.from_utc_datetime(&at("2024-03-10 20:30"));
+        // Five hours east of wherever the tests run, so it never matches the local zone.
+        let offset = local_offset(date).local_minus_utc() + 5 * 3600;
+        let local = recorded_local_time(date, Some(offset));
+        assert_eq!(local.offset().local_minus_utc(), offset);
+        assert_eq!(
+            local.naive_local(),
+            at("2024-03-10 20:30") + Duration::seconds(offset as i64)
+        );
+    }
+
+    #[test]
+    fn diary_day_follows_the_recorded_zone() {
+        // 01:00 on the 10th in UTC+9, while still the 9th in UTC.
+        let date = Utc.from_utc_datetime(&at("2024-03-09 16:00"));
+        let local = recorded_local_time(date, Some(9 * 3600)).naive_local();
+        assert_eq!(local_diary_date(local, 4), day("2024-03-09"));
+        assert_eq!(local_diary_date(local, 0), day("2024-03-10"));
+    }
+
+    #[test]
+    fn missing_offset_falls_back_to_the_local_zone() {
+        let date = Utc.from_utc_datetime(&at("2024-03-10 20:30"));
+        assert_eq!(
+            recorded_local_time(date, None).naive_local(),
+            DateTime::<Local>::from(date).naive_local()
+        );
+    }
+}
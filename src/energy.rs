@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const KJ_PER_KCAL: f32 = 4.184;
+
+/// Unit energy is shown and entered in, always stored as kcal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EnergyUnit {
+    #[default]
+    Kcal,
+    Kj,
+}
+
+impl EnergyUnit {
+    pub const ALL: [EnergyUnit; 2] = [EnergyUnit::Kcal, EnergyUnit::Kj];
+
+    pub fn in_unit(self, kcal: f32) -> f32 {
+        match self {
+            EnergyUnit::Kcal => kcal,
+            EnergyUnit::Kj => kcal * KJ_PER_KCAL,
+        }
+    }
+
+    pub fn to_kcal(self, value: f32) -> f32 {
+        match self {
+            EnergyUnit::Kcal => value,
+            EnergyUnit::Kj => value / KJ_PER_KCAL,
+        }
+    }
+}
+
+impl fmt::Display for EnergyUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnergyUnit::Kcal => write!(f, "kcal"),
+            EnergyUnit::Kj => write!(f, "kJ"),
+        }
+    }
+}
+
+/// Macro nutrients that provide energy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nutrient {
+    Carbs,
+    Fats,
+    Protein,
+    Alcohol,
+}
+
+impl Nutrient {
+    pub const ALL: [Nutrient; 4] = [
+        Nutrient::Carbs,
+        Nutrient::Fats,
+        Nutrient::Protein,
+        Nutrient::Alcohol,
+    ];
+}
+
+impl fmt::Display for Nutrient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Nutrient::Carbs => write!(f, "Carbs"),
+            Nutrient::Fats => write!(f, "Fats"),
+            Nutrient::Protein => write!(f, "Protein"),
+            Nutrient::Alcohol => write!(f, "Alcohol"),
+        }
+    }
+}
+
+/// kcal per gram of each nutrient, the Atwater factors unless changed in the settings.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnergyFactors {
+    pub carbs: f32,
+    pub fats: f32,
+    pub protein: f32,
+    pub alcohol: f32,
+}
+
+impl Default for EnergyFactors {
+    fn default() -> Self {
+        EnergyFactors {
+            carbs: 4.0,
+            fats: 9.0,
+            protein: 4.0,
+            alcohol: 7.0,
+        }
+    }
+}
+
+impl EnergyFactors {
+    pub fn calories(&self, carbs: f32, fats: f32, protein: f32, alcohol: f32) -> f32 {
+        carbs * self.carbs + fats * self.fats + protein * self.protein + alcohol * self.alcohol
+    }
+
+    pub fn get(&self, nutrient: Nutrient) -> f32 {
+        match nutrient {
+            Nutrient::Carbs => self.carbs,
+            Nutrient::Fats => self.fats,
+            Nutrient::Protein => self.protein,
+            Nutrient::Alcohol => self.alcohol,
+        }
+    }
+
+    pub fn set(&mut self, nutrient: Nutrient, factor: f32) {
+        match nutrient {
+            Nutrient::Carbs => self.carbs = factor,
+            Nutrient::Fats => self.fats = factor,
+            Nutrient::Protein => self.protein = factor,
+            Nutrient::Alcohol => self.alcohol = factor,
+        }
+    }
+}
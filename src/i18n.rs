@@ -0,0 +1,229 @@
+use chrono::{DateTime, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+
+/// Languages of the interface, English is the source language and needs no catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::German];
+
+    /// Language of the environment, English when it is not translated.
+    pub fn system() -> Locale {
+        let language = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|variable| env::var(variable).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        if language.starts_with("de") {
+            Locale::German
+        } else {
+            Locale::English
+        }
+    }
+
+    /// Translations keyed by the English message.
+    fn catalog(self) -> Option<&'static str> {
+        match self {
+            Locale::English => None,
+            Locale::German => Some(include_str!("../locales/de.json")),
+        }
+    }
+
+    fn decimal_separator(self) -> char {
+        match self {
+            Locale::English => '.',
+            Locale::German => ',',
+        }
+    }
+
+    fn date_format(self) -> &'static str {
+        match self {
+            Locale::English => "%Y-%m-%d",
+            Locale::German => "%d.%m.%Y",
+        }
+    }
+
+    fn month_format(self) -> &'static str {
+        match self {
+            Locale::English => "%Y-%m",
+            Locale::German => "%m.%Y",
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Locale::English => write!(f, "English"),
+            Locale::German => write!(f, "Deutsch"),
+        }
+    }
+}
+
+/// Translated messages and number and date conventions of a locale.
+pub struct Catalog {
+    locale: Locale,
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn new(locale: Locale) -> Self {
+        let messages = locale
+            .catalog()
+            .map(|catalog| serde_json::from_str(catalog).expect("Failed to parse catalog"))
+            .unwrap_or_default();
+        Catalog { locale, messages }
+    }
+
+    /// Translation of an English message, the message itself when it is not translated.
+    pub fn tr<'a>(&'a self, message: &'a str) -> &'a str {
+        self.messages.get(message).map_or(message, String::as_str)
+    }
+
+    /// Translates a message and fills its `{0}`, `{1}`, … placeholders with the arguments at
+    /// those positions, so translations can reorder them.
+    pub fn tr_args(&self, message: &str, args: &[&dyn fmt::Display]) -> String {
+        let mut text = String::new();
+        let mut rest = self.tr(message);
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let placeholder = after.find('}').and_then(|end| {
+                let arg = args.get(after[..end].parse::<usize>().ok()?)?;
+                Some((end, arg))
+            });
+            match placeholder {
+                Some((end, arg)) => {
+                    text.push_str(&arg.to_string());
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = after;
+                }
+            }
+        }
+        text.push_str(rest);
+        text
+    }
+
+    pub fn number(&self, value: f32, precision: usize) -> String {
+        self.localize_number(format!("{:.precision$}", value))
+    }
+
+    /// Number with an explicit sign, e.g. for changes.
+    pub fn signed_number(&self, value: f32, precision: usize) -> String {
+        self.localize_number(format!("{:+.precision$}", value))
+    }
+
+    /// Number as it is typed into inputs, without padding zeros.
+    pub fn input(&self, value: f32) -> String {
+        self.localize_number(value.to_string())
+    }
+
+    /// Parses a number typed in this locale, a point is accepted as decimal separator too
+    /// unless it reads as a thousands separator of the locale, e.g. German "1.000".
+    pub fn parse_number(&self, input: &str) -> Option<f32> {
+        let input = input.trim();
+        if self.locale.decimal_separator() == ',' && is_grouped(input) {
+            return None;
+        }
+        self.normalize_number(input).parse().ok()
+    }
+
+    /// Replaces the decimal separator of the locale with a point.
+    fn normalize_number(&self, input: &str) -> String {
+        input.replace(self.locale.decimal_separator(), ".")
+    }
+
+    pub fn date(&self, date: NaiveDate) -> String {
+        date.format(self.locale.date_format()).to_string()
+    }
+
+    pub fn month(&self, date: NaiveDate) -> String {
+        date.format(self.locale.month_format()).to_string()
+    }
+
+    pub fn date_time<Tz: TimeZone>(&self, date: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: fmt::Display,
+    {
+        format!(
+            "{} {}",
+            self.date(date.naive_local().date()),
+            date.format("%H:%M")
+        )
+    }
+
+    fn localize_number(&self, number: String) -> String {
+        number.replace('.', &self.locale.decimal_separator().to_string())
+    }
+}
+
+/// Whether a number is written with points grouping thousands, e.g. "1.000" or "-12.345.678"
+/// but not "0.500".
+fn is_grouped(input: &str) -> bool {
+    let digits = |group: &str| !group.is_empty() && group.chars().all(|c| c.is_ascii_digit());
+    let mut groups = input.trim_start_matches(['-', '+']).split('.');
+    let first = groups.next().unwrap_or_default();
+    let rest: Vec<&str> = groups.collect();
+    digits(first)
+        && !first.starts_with('0')
+        && first.len() <= 3
+        && !rest.is_empty()
+        && rest.iter().all(|group| group.len() == 3 && digits(group))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_filled_by_index() {
+        let catalog = Catalog::new(Locale::English);
+        assert_eq!(
+            catalog.tr_args("{1} before {0}", &[&"a", &"b"]),
+            "b before a"
+        );
+        assert_eq!(catalog.tr_args("{0} and {0}", &[&1]), "1 and 1");
+    }
+
+    #[test]
+    fn arguments_are_not_filled_again() {
+        let catalog = Catalog::new(Locale::English);
+        assert_eq!(catalog.tr_args("{0}: {1}", &[&"{1}", &"x"]), "{1}: x");
+        assert_eq!(catalog.tr_args("{2} {x}", &[&"a"]), "{2} {x}");
+    }
+
+    #[test]
+    fn german_accepts_both_decimal_separators() {
+        let catalog = Catalog::new(Locale::German);
+        assert_eq!(catalog.parse_number("1,5"), Some(1.5));
+        assert_eq!(catalog.parse_number(" 1.5 "), Some(1.5));
+        assert_eq!(catalog.parse_number("1.2345"), Some(1.2345));
+    }
+
+    #[test]
+    fn german_rejects_thousands_separators() {
+        let catalog = Catalog::new(Locale::German);
+        assert_eq!(catalog.parse_number("1.000"), None);
+        assert_eq!(catalog.parse_number("-12.345.678"), None);
+        assert_eq!(catalog.parse_number("1000"), Some(1000.0));
+        assert_eq!(catalog.parse_number("0.500"), Some(0.5));
+        assert_eq!(catalog.parse_number("0.125"), Some(0.125));
+    }
+
+    #[test]
+    fn english_reads_points_as_decimals() {
+        let catalog = Catalog::new(Locale::English);
+        assert_eq!(catalog.parse_number("1.000"), Some(1.0));
+    }
+}
@@ -1,28 +1,80 @@
 #![windows_subsystem = "windows"]
 
-use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
-use home::home_dir;
+use energy::{EnergyFactors, EnergyUnit, Nutrient};
+use i18n::{Catalog, Locale};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
 use iced::alignment::{Horizontal, Vertical};
 use iced::widget::container::Appearance;
+use iced::widget::scrollable::Viewport;
 use iced::widget::text::Shaping;
-use iced::widget::{Button, Column, Container, Row, Scrollable, Space, Text, TextInput};
-use iced::{executor, theme, Alignment, Application, Background, Color, Command, Element, Length, Settings, Theme, window};
+use iced::widget::{
+    Button, Checkbox, Column, Container, PickList, Radio, Row, Scrollable, Space, Text, TextInput,
+};
+use calendar::{diary_today, local_offset, sort_and_migrate, Recorded};
+use iced::widget::text_input;
+use iced::{executor, subscription, theme, Alignment, Application, Background, Command, Element, Event, Length, Settings, Size, Subscription, Theme, window};
 use iced_aw::floating_element::{Anchor, Offset};
 use iced_aw::{FloatingElement, TabBar, TabLabel};
-use rust_fuzzy_search::fuzzy_compare;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use iced::window::Position;
+use nutrition::{parse_servings, Per100, ServingUnit};
+use settings::UserSettings;
+use stats::{monthly_adherence, streak, Goals};
+use themes::{ThemeChoice, ACCENT_PRESETS};
+use templates::{MealTemplate, TemplateItem};
+use water::{WaterEntry, WaterLog, QUICK_ADD_VOLUMES};
+use weight::{week_start, WeightEntry, WeightLog, WeightUnit};
+
+mod calendar;
+mod energy;
+mod i18n;
+mod nutrition;
+mod search;
+mod settings;
+mod shortcuts;
+mod stats;
+mod tdee;
+mod templates;
+mod themes;
+mod water;
+mod weight;
+
+/// Rows built above and below the visible part of the feed, so fast scrolling doesn't
+/// show blank space for a frame.
+const FEED_OVERSCAN_ROWS: usize = 10;
+
+/// Text size the interface is laid out at, the text size setting scales the whole interface
+/// from it.
+const BASE_TEXT_SIZE: f32 = 16.0;
+
+/// Line height of iced's text relative to its size, and the default padding of buttons.
+const LINE_HEIGHT: f32 = 1.3;
+const BUTTON_PADDING: f32 = 5.0;
+
+/// Padding of striped table rows, e.g. in the feed.
+const ROW_PADDING: u16 = 10;
+
+/// Number of changes to foods and the feed that can be undone.
+const UNDO_LIMIT: usize = 20;
+
+/// Number of foods listed in the recent and frequent sections of the food tab.
+const FOOD_SHORTLIST_LEN: usize = 10;
 
 pub fn main() -> iced::Result {
     //window::icon::from_rgba()
+    let settings = UserSettings::load();
     Macros::run(Settings {
         id: None,
         window: window::Settings {
-            size: (1000, 600),
+            size: (settings.window_width, settings.window_height),
             position: Position::Centered,
             min_size: None,
             max_size: None,
@@ -34,29 +86,142 @@ pub fn main() -> iced::Result {
             icon: None,
             platform_specific: Default::default(),
         },
-        flags: (),
         default_font: Default::default(),
-        default_text_size: 16.0,
+        default_text_size: BASE_TEXT_SIZE,
+        flags: settings,
         antialiasing: true,
-        exit_on_close_request: true,
+        exit_on_close_request: false,
     })
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     ChangeTab(usize),
+    ShortcutTab(usize),
+    FocusSearch,
+    /// Shows a tab and focuses one of its inputs, for palette actions that need typed values.
+    FocusInput(TabType, text_input::Id),
+    LogTopSearchResult,
+    EditTopSearchResult,
+    Undo,
+    TogglePalette,
+    Escape,
+    PaletteQueryChanged(String),
+    PaletteSubmit,
+    PaletteAction(Box<Message>),
+    ThemeChoiceChanged(ThemeChoice),
+    LocaleChanged(Option<Locale>),
+    AccentSelected(Option<String>),
+    AccentInputChanged(String),
+    WindowWidthChanged(String),
+    WindowHeightChanged(String),
+    WindowResized(u32, u32),
+    WindowCloseRequested,
+    TextSizeChanged(String),
+    PrecisionChanged(String),
+    DayStartChanged(String),
+    EnergyUnitChanged(EnergyUnit),
+    EnergyFactorChanged(Nutrient, String),
+    DataDirChanged(String),
+    DataDirSubmitted,
+    ExportDirChanged(String),
+    ExportDirSubmitted,
     ChangeSearchText(String),
     AddFood(AddFood),
     AddFoodNameChanged(String),
+    AddFoodBrandChanged(String),
+    AddFoodAliasesChanged(String),
+    AddFoodTagInputChanged(String),
+    AddFoodTagAdded,
+    AddFoodTagRemoved(String),
+    ToggleTagFilter(String),
     AddFoodServingSizeChanged(String),
     AddFoodCarbsChanged(String),
     AddFoodFatsChanged(String),
     AddFoodProteinsChanged(String),
+    AddFoodAlcoholChanged(String),
+    AddFoodEnergyChanged(String),
+    AddFoodWaterChanged(String),
+    AddFoodServingWeightChanged(String),
+    AddFoodServingUnitChanged(ServingUnit),
+    AddFoodPer100Toggled(bool),
+    AddFoodApplyToPastChanged(bool),
+    RestoreFoodRevision(usize),
     FoodServingCurrentServingSizeChanged(u32, String),
     AddFeedEntry(Food),
     ModifyFood(Food),
     DeleteFood(Food),
+    ToggleFavorite(u32),
+    ToggleFoodSelected(u32),
+    SelectAllFoods,
+    ClearFoodSelection,
+    ShowArchivedToggled(bool),
+    BulkTagChanged(String),
+    BulkBrandChanged(String),
+    BulkAddTag,
+    BulkSetBrand,
+    BulkArchive(bool),
+    BulkDelete,
+    BulkExport,
+    MergeFood(MergeFood),
+    MergeDuplicateSelected(FoodChoice),
+    MergeSurvivorSelected(FoodChoice),
+    MergeSuggestionSelected(u32, u32),
     DeleteFeedEntry(u32),
+    ConfirmDelete(ConfirmDelete),
+    CopyFeedEntryToToday(u32),
+    CopyDayToToday(NaiveDate),
+    ToggleFeedEntrySelected(u32),
+    NewTemplateNameChanged(String),
+    CreateTemplate,
+    RenameTemplate(u32, String),
+    TemplateScaleChanged(u32, String),
+    TemplateItemAmountChanged(u32, usize, String),
+    AddTemplateItem(u32, FoodChoice),
+    RemoveTemplateItem(u32, usize),
+    LogTemplate(u32),
+    DeleteTemplate(u32),
+    RelinkFeedEntry(u32, u32),
+    WeightInputChanged(String),
+    WeightUnitChanged(WeightUnit),
+    AddWeightEntry,
+    TargetRateChanged(String),
+    AddWater(f32),
+    DeleteWaterEntry(u32),
+    WaterTargetChanged(String),
+    QuickAddLabelChanged(String),
+    QuickAddCarbsChanged(String),
+    QuickAddFatsChanged(String),
+    QuickAddProteinChanged(String),
+    QuickAddAlcoholChanged(String),
+    QuickAdd,
+    CalorieGoalChanged(String),
+    GoalToleranceChanged(String),
+    ApplySuggestedGoal(f32),
+    DeleteWeightEntry(u32),
+    FeedScrolled(Viewport),
+}
+
+impl Message {
+    /// Whether the message may change foods or the feed and should be recorded for undo.
+    fn is_undoable(&self) -> bool {
+        matches!(
+            self,
+            Message::AddFood(AddFood::FinishAdd)
+                | Message::AddFeedEntry(_)
+                | Message::ToggleFavorite(_)
+                | Message::MergeFood(MergeFood::FinishMerge)
+                | Message::BulkAddTag
+                | Message::BulkSetBrand
+                | Message::BulkArchive(_)
+                | Message::ConfirmDelete(ConfirmDelete::Archive | ConfirmDelete::Delete)
+                | Message::CopyFeedEntryToToday(_)
+                | Message::CopyDayToToday(_)
+                | Message::RelinkFeedEntry(_, _)
+                | Message::QuickAdd
+                | Message::LogTemplate(_)
+        )
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -66,84 +231,148 @@ pub enum AddFood {
     FinishAdd,
 }
 
+#[derive(Debug, Copy, Clone)]
+pub enum MergeFood {
+    Cancel,
+    BeginMerge,
+    FinishMerge,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum ConfirmDelete {
+    Cancel,
+    /// Archives the food instead, keeping its diary entries.
+    Archive,
+    Delete,
+}
+
+/// Deletion waiting for confirmation, shown in place of the current tab.
+#[derive(Debug, Copy, Clone)]
+enum PendingDelete {
+    Food(u32),
+    /// The foods selected in the food tab.
+    SelectedFoods,
+    FeedEntry(u32),
+}
+
 pub struct Tab {
     title: String,
     tab_type: TabType,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TabType {
     Feed,
     Food,
+    Weight,
+    Stats,
+    Templates,
+    Integrity,
+    Settings,
 }
 
 struct Macros {
     tabs: Vec<Tab>,
     current_tab: usize,
     search_text: String,
+    palette_open: bool,
+    palette_query: String,
+    settings: UserSettings,
+    i18n: Catalog,
+    /// Built from the theme settings whenever they change, detecting the system mode is slow.
+    theme: Theme,
+    accent_input: String,
+    window_width_input: String,
+    window_height_input: String,
+    text_size_input: String,
+    precision_input: String,
+    day_start_input: String,
+    /// Inputs of the energy factors, in the order of `Nutrient::ALL`.
+    energy_factor_inputs: Vec<String>,
+    data_dir_input: String,
+    /// Why the data directory typed into the settings couldn't be switched to.
+    data_dir_error: Option<String>,
+    export_dir_input: String,
+    undo_stack: Vec<UndoSnapshot>,
+    /// Incremented whenever foods or the feed are saved, used to detect undoable changes.
+    data_version: u64,
+    /// Tags a food must all have to be listed in the food tab.
+    tag_filter: BTreeSet<String>,
+    show_archived: bool,
+    selected_foods: BTreeSet<u32>,
+    bulk_tag: String,
+    bulk_brand: String,
+    /// Result of the last bulk action that has no other visible effect, e.g. an export.
+    food_status: String,
     adding_food: bool,
     add_food_name: String,
+    add_food_brand: String,
+    add_food_aliases: String,
+    add_food_tags: Vec<String>,
+    add_food_tag_input: String,
     add_food_carbs: String,
     add_food_serving_size: String,
     add_food_fats: String,
     add_food_proteins: String,
+    add_food_alcohol: String,
+    add_food_energy: String,
+    /// Energy in kcal loaded into the form, kept while its input is unchanged since converting
+    /// back from kJ is inexact.
+    add_food_loaded_energy: Option<f32>,
+    add_food_water: String,
+    add_food_serving_weight: String,
+    add_food_serving_unit: ServingUnit,
+    /// Whether the macros in the form are per 100 g/ml rather than per serving.
+    add_food_per_100: bool,
+    /// Whether editing a food's values also changes entries logged before the edit.
+    add_food_apply_to_past: bool,
+    merging_foods: bool,
+    pending_delete: Option<PendingDelete>,
+    merge_duplicate: Option<u32>,
+    merge_survivor: Option<u32>,
+    /// Pairs of foods with similar names, found when the merge view opens.
+    duplicate_foods: Vec<(u32, u32)>,
+    /// Foods with names similar to the one entered in the add/edit form.
+    similar_food_ids: Vec<u32>,
     foods: Vec<Food>,
+    food_index: HashMap<u32, usize>,
     feed: Vec<FeedEntry>,
+    days: Vec<Day>,
+    /// Scroll position of the feed, only the rows around it are built.
+    feed_offset: f32,
     config_path: PathBuf,
     next_food_id: u32,
     next_feed_id: u32,
+    weight_log: WeightLog,
+    weight_input: String,
+    target_rate_input: String,
+    next_weight_id: u32,
+    water_log: WaterLog,
+    water_target_input: String,
+    quick_add_label: String,
+    quick_add_carbs: String,
+    quick_add_fats: String,
+    quick_add_protein: String,
+    quick_add_alcohol: String,
+    next_water_id: u32,
+    goals: Goals,
+    templates: Vec<MealTemplate>,
+    next_template_id: u32,
+    selected_feed_entries: BTreeSet<u32>,
+    new_template_name: String,
+    calorie_goal_input: String,
+    goal_tolerance_input: String,
 }
 
 impl Application for Macros {
     type Executor = executor::Default;
     type Message = Message;
     type Theme = Theme;
-    type Flags = ();
-
-    fn new(_flags: Self::Flags) -> (Self, Command<Self::Message>) {
-        let config_path = home_dir().unwrap_or(PathBuf::from("")).join(".macros");
-        fs::create_dir_all(&config_path).expect("Failed to create config directory");
-        let foods =
-            fs::read_to_string(config_path.join("foods.json")).unwrap_or(String::from("[]"));
-        let mut foods =
-            serde_json::from_str::<Vec<Food>>(&foods).expect("Failed to parse foods.json");
-        let next_food_id = foods.iter().fold(0, |acc, food| max(acc, food.id + 1));
-        foods
-            .iter_mut()
-            .for_each(|food| food.current_serving_size = String::from("1.0"));
+    type Flags = UserSettings;
 
-        let feed = fs::read_to_string(config_path.join("feed.json")).unwrap_or(String::from("[]"));
-        let mut feed =
-            serde_json::from_str::<Vec<FeedEntry>>(&feed).expect("Failed to parse feed.json");
-        let next_feed_id = feed.iter().fold(0, |acc, food| max(acc, food.id + 1));
-        feed.sort_by_key(|entry| entry.date);
-        (
-            Macros {
-                tabs: vec![
-                    Tab {
-                        title: String::from("Feed"),
-                        tab_type: TabType::Feed,
-                    },
-                    Tab {
-                        title: String::from("Food"),
-                        tab_type: TabType::Food,
-                    },
-                ],
-                current_tab: 0,
-                search_text: "".to_string(),
-                adding_food: false,
-                add_food_name: "".to_string(),
-                add_food_carbs: "".to_string(),
-                add_food_serving_size: "".to_string(),
-                add_food_fats: "".to_string(),
-                add_food_proteins: "".to_string(),
-                foods,
-                feed,
-                config_path,
-                next_food_id,
-                next_feed_id,
-            },
-            Command::none(),
-        )
+    fn new(settings: Self::Flags) -> (Self, Command<Self::Message>) {
+        let macros = Macros::load(settings).unwrap_or_else(|error| panic!("{}", error));
+        (macros, Command::none())
     }
 
     fn title(&self) -> String {
@@ -151,6 +380,19 @@ impl Application for Macros {
     }
 
     fn update(&mut self, message: Message) -> Command<Self::Message> {
+        // The feed's scrollable starts at the top again whenever the feed is shown after
+        // something else replaced it.
+        if !self.feed_shown() {
+            self.feed_offset = 0.0;
+        }
+
+        let undo_snapshot = self.records_undo(&message).then(|| UndoSnapshot {
+            foods: self.foods.clone(),
+            feed: self.feed.clone(),
+            templates: self.templates.clone(),
+            data_version: self.data_version,
+        });
+
         match message {
             Message::ChangeTab(index) => {
                 if index < self.tabs.len() {
@@ -159,26 +401,231 @@ impl Application for Macros {
                     panic!("Invalid tab index")
                 }
             }
+            Message::ShortcutTab(index) => {
+                if index < self.tabs.len() {
+                    self.current_tab = index;
+                }
+            }
+            Message::FocusSearch => {
+                self.show_food_list();
+                return text_input::focus(search_input_id());
+            }
+            Message::FocusInput(tab_type, id) => {
+                if tab_type == TabType::Food {
+                    self.show_food_list();
+                } else {
+                    self.show_tab(tab_type);
+                }
+                return text_input::focus(id);
+            }
+            Message::LogTopSearchResult => {
+                let Some(food) = self.top_search_result().cloned() else {
+                    return Command::none();
+                };
+                // Not undoable itself, logging the entry takes the undo snapshot.
+                return self.update(Message::AddFeedEntry(food));
+            }
+            Message::EditTopSearchResult => {
+                let Some(food) = self.top_search_result().cloned() else {
+                    return Command::none();
+                };
+                self.show_food_list();
+                return self.update(Message::ModifyFood(food));
+            }
+            Message::Undo => {
+                let Some(snapshot) = self.undo_stack.pop() else {
+                    return Command::none();
+                };
+                self.pending_delete = None;
+                self.foods = snapshot.foods;
+                self.feed = snapshot.feed;
+                self.templates = snapshot.templates;
+                self.sort_foods();
+                self.selected_foods
+                    .retain(|food_id| self.food_index.contains_key(food_id));
+                self.refresh_days();
+                self.save_foods();
+                self.save_feed();
+                self.save_templates();
+                self.data_version = snapshot.data_version;
+            }
+            Message::TogglePalette => {
+                self.palette_open = !self.palette_open;
+                self.palette_query = "".to_string();
+                if self.palette_open {
+                    return text_input::focus(palette_input_id());
+                }
+            }
+            Message::Escape => {
+                if self.palette_open {
+                    self.palette_open = false;
+                } else if self.pending_delete.is_some() {
+                    return self.update(Message::ConfirmDelete(ConfirmDelete::Cancel));
+                }
+            }
+            Message::PaletteQueryChanged(new_palette_query) => {
+                self.palette_query = new_palette_query;
+            }
+            Message::PaletteSubmit => {
+                if let Some((_, action)) = self.palette_actions().into_iter().next() {
+                    return self.update(Message::PaletteAction(Box::new(action)));
+                }
+            }
+            Message::PaletteAction(action) => {
+                self.palette_open = false;
+                return self.update(*action);
+            }
+            Message::ThemeChoiceChanged(choice) => {
+                self.settings.theme.choice = choice;
+                self.theme = self.settings.theme.theme();
+                self.save_settings();
+            }
+            Message::LocaleChanged(locale) => {
+                self.settings.locale = locale;
+                self.i18n = Catalog::new(self.settings.locale());
+                self.reset_number_inputs();
+                let i18n = &self.i18n;
+                self.foods
+                    .iter_mut()
+                    .for_each(|food| food.current_serving_size = i18n.input(1.0));
+                self.templates
+                    .iter_mut()
+                    .for_each(|template| template.reset_inputs(i18n));
+                self.save_settings();
+            }
+            Message::WindowWidthChanged(new_window_width_input) => {
+                self.window_width_input = new_window_width_input;
+                if let Ok(width) = self.window_width_input.trim().parse::<u32>() {
+                    if (400..=10000).contains(&width) {
+                        self.settings.window_width = width;
+                        self.save_settings();
+                        return window::resize(Size::new(width, self.settings.window_height));
+                    }
+                }
+            }
+            Message::WindowHeightChanged(new_window_height_input) => {
+                self.window_height_input = new_window_height_input;
+                if let Ok(height) = self.window_height_input.trim().parse::<u32>() {
+                    if (300..=10000).contains(&height) {
+                        self.settings.window_height = height;
+                        self.save_settings();
+                        return window::resize(Size::new(self.settings.window_width, height));
+                    }
+                }
+            }
+            Message::WindowResized(width, height) => {
+                // Reported in interface units, which the text size scales.
+                let scale = self.scale_factor();
+                let width = (width as f64 * scale).round() as u32;
+                let height = (height as f64 * scale).round() as u32;
+                if (width, height) == (self.settings.window_width, self.settings.window_height) {
+                    return Command::none();
+                }
+                self.settings.window_width = width;
+                self.settings.window_height = height;
+                self.window_width_input = width.to_string();
+                self.window_height_input = height.to_string();
+            }
+            Message::WindowCloseRequested => {
+                // The size is only saved here, resizing sends an event per frame.
+                self.save_settings();
+                return window::close();
+            }
+            Message::TextSizeChanged(new_text_size_input) => {
+                self.text_size_input = new_text_size_input;
+                if let Some(text_size) = self.i18n.parse_number(&self.text_size_input) {
+                    if (8.0..=40.0).contains(&text_size) {
+                        self.settings.text_size = text_size;
+                        self.save_settings();
+                    }
+                }
+            }
+            Message::PrecisionChanged(new_precision_input) => {
+                self.precision_input = new_precision_input;
+                if let Ok(precision) = self.precision_input.trim().parse::<usize>() {
+                    if precision <= 4 {
+                        self.settings.precision = precision;
+                        self.save_settings();
+                    }
+                }
+            }
+            Message::DayStartChanged(new_day_start_input) => {
+                self.day_start_input = new_day_start_input;
+                if let Ok(hour) = self.day_start_input.trim().parse::<u32>() {
+                    if hour < 24 {
+                        self.settings.day_start_hour = hour;
+                        self.save_settings();
+                        self.refresh_days();
+                    }
+                }
+            }
+            Message::EnergyUnitChanged(unit) => {
+                self.settings.energy_unit = unit;
+                self.reset_number_inputs();
+                self.save_settings();
+            }
+            Message::EnergyFactorChanged(nutrient, new_energy_factor_input) => {
+                let Some(index) = Nutrient::ALL.iter().position(|other| *other == nutrient) else {
+                    return Command::none();
+                };
+                self.energy_factor_inputs[index] = new_energy_factor_input;
+                if let Some(factor) = self.i18n.parse_number(&self.energy_factor_inputs[index]) {
+                    if (0.0..=100.0).contains(&factor) {
+                        self.settings.energy_factors.set(nutrient, factor);
+                        self.save_settings();
+                        self.refresh_days();
+                    }
+                }
+            }
+            Message::DataDirChanged(new_data_dir_input) => {
+                self.data_dir_input = new_data_dir_input;
+                self.data_dir_error = None;
+            }
+            Message::DataDirSubmitted => {
+                let previous_data_dir = self.settings.data_dir.clone();
+                self.settings.data_dir = path_setting(&self.data_dir_input);
+                // Reload everything from the new directory as on launch, keeping the current
+                // data if it can't be read.
+                match Macros::load(self.settings.clone()) {
+                    Ok(mut macros) => {
+                        macros.current_tab = self.current_tab;
+                        *self = macros;
+                        self.save_settings();
+                    }
+                    Err(error) => {
+                        self.settings.data_dir = previous_data_dir;
+                        self.data_dir_error = Some(error);
+                    }
+                }
+            }
+            Message::ExportDirChanged(new_export_dir_input) => {
+                self.export_dir_input = new_export_dir_input;
+            }
+            Message::ExportDirSubmitted => {
+                self.settings.export_dir = path_setting(&self.export_dir_input);
+                self.save_settings();
+            }
+            Message::AccentSelected(accent) => {
+                self.accent_input = accent.clone().unwrap_or_default();
+                self.settings.theme.accent = accent;
+                self.theme = self.settings.theme.theme();
+                self.save_settings();
+            }
+            Message::AccentInputChanged(new_accent_input) => {
+                self.accent_input = new_accent_input;
+                if self.accent_input.trim().is_empty() {
+                    self.settings.theme.accent = None;
+                } else if themes::parse_hex(&self.accent_input).is_some() {
+                    self.settings.theme.accent = Some(self.accent_input.trim().to_string());
+                } else {
+                    return Command::none();
+                }
+                self.theme = self.settings.theme.theme();
+                self.save_settings();
+            }
             Message::ChangeSearchText(new_search_text) => {
                 self.search_text = new_search_text;
-                self.foods.iter_mut().for_each(|food| {
-                    food.relevance =
-                        fuzzy_compare(&food.name.to_lowercase(), &self.search_text.to_lowercase())
-                            + if food
-                                .name
-                                .to_lowercase()
-                                .contains(&self.search_text.to_lowercase())
-                            {
-                                1.0
-                            } else {
-                                0.0
-                            };
-                });
-                self.foods.sort_by(|a, b| {
-                    b.relevance
-                        .partial_cmp(&a.relevance)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
+                self.sort_foods();
             }
             Message::AddFood(adding_food) => {
                 match adding_food {
@@ -191,49 +638,94 @@ impl Application for Macros {
                         self.adding_food = true;
                     }
                     AddFood::FinishAdd => {
-                        if self.add_food_name.trim().is_empty()
-                            || self.add_food_serving_size.trim().is_empty()
-                            || self.add_food_carbs.parse::<f32>().is_err()
-                            || self.add_food_fats.parse::<f32>().is_err()
-                            || self.add_food_proteins.parse::<f32>().is_err()
-                        {
+                        if !self.food_form_errors().is_valid() {
                             return Command::none();
                         }
 
+                        let number = |input: &str| self.i18n.parse_number(input);
+                        let serving_weight = number(&self.add_food_serving_weight);
+                        let values = Per100 {
+                            carbs: number(&self.add_food_carbs).unwrap(),
+                            fats: number(&self.add_food_fats).unwrap(),
+                            protein: number(&self.add_food_proteins).unwrap(),
+                            alcohol: number(&self.add_food_alcohol).unwrap_or(0.0),
+                            energy: number(&self.add_food_energy).map(|energy| {
+                                let unit = self.settings.energy_unit;
+                                match self.add_food_loaded_energy {
+                                    Some(kcal) if unit.in_unit(kcal) == energy => kcal,
+                                    _ => unit.to_kcal(energy),
+                                }
+                            }),
+                        };
+                        let (per_100, serving) = match serving_weight {
+                            Some(serving_weight) if self.add_food_per_100 => {
+                                (Some(values), values.to_serving(serving_weight))
+                            }
+                            Some(serving_weight) => {
+                                (Some(Per100::from_serving(values, serving_weight)), values)
+                            }
+                            None => (None, values),
+                        };
+
                         self.adding_food = false;
 
-                        let new_food = Food {
+                        let mut new_food = Food {
                             id: self.next_food_id,
                             name: self.add_food_name.clone(),
-                            brand: "".to_string(),
-                            carbs: self.add_food_carbs.clone().parse().unwrap(),
-                            fats: self.add_food_fats.clone().parse().unwrap(),
-                            protein: self.add_food_proteins.clone().parse().unwrap(),
+                            brand: self.add_food_brand.trim().to_string(),
+                            aliases: self
+                                .add_food_aliases
+                                .split(',')
+                                .map(|alias| alias.trim().to_string())
+                                .filter(|alias| !alias.is_empty())
+                                .collect(),
+                            tags: self.add_food_tags.clone(),
+                            carbs: serving.carbs,
+                            fats: serving.fats,
+                            protein: serving.protein,
+                            alcohol: serving.alcohol,
+                            energy: serving.energy,
+                            serving_weight,
+                            serving_unit: self.add_food_serving_unit,
+                            per_100,
+                            entered_per_100: self.add_food_per_100 && per_100.is_some(),
+                            water: number(&self.add_food_water).unwrap_or(0.0),
+                            favorite: self
+                                .food(self.next_food_id)
+                                .map(|food| food.favorite)
+                                .unwrap_or(false),
+                            archived: self
+                                .food(self.next_food_id)
+                                .map(|food| food.archived)
+                                .unwrap_or(false),
                             serving_size: self.add_food_serving_size.to_string(),
-                            current_serving_size: String::from("1.0"),
-                            relevance: fuzzy_compare(
-                                &self.add_food_name.to_lowercase(),
-                                &self.search_text.to_lowercase(),
-                            ) + if self
-                                .add_food_name
-                                .to_lowercase()
-                                .contains(&self.search_text.to_lowercase())
-                            {
-                                1.0
-                            } else {
-                                0.0
-                            },
+                            current_serving_size: self.i18n.input(1.0),
+                            revisions: Vec::new(),
+                            relevance: 0.0,
+                            highlight: Vec::new(),
                         };
 
-                        if new_food.carbs < 0.0
-                            || new_food.carbs > 1000.0
-                            || new_food.fats < 0.0
-                            || new_food.fats > 1000.0
-                            || new_food.protein < 0.0
-                            || new_food.protein > 1000.0
-                        {
-                            return Command::none();
+                        if let Some(old_food) = self.food(new_food.id) {
+                            let mut revisions = old_food.revisions.clone();
+                            let old_revision = old_food.current_revision();
+                            if !old_revision.same_values(&new_food.current_revision()) {
+                                if self.add_food_apply_to_past {
+                                    revisions
+                                        .iter_mut()
+                                        .for_each(|revision| revision.valid_until = None);
+                                }
+                                revisions.push(FoodRevision {
+                                    valid_until: if self.add_food_apply_to_past {
+                                        None
+                                    } else {
+                                        Some(old_revision.replaced)
+                                    },
+                                    ..old_revision
+                                });
+                            }
+                            new_food.revisions = revisions;
                         }
+
                         self.foods = self
                             .foods
                             .iter()
@@ -241,40 +733,79 @@ impl Application for Macros {
                             .cloned()
                             .collect();
                         self.foods.push(new_food);
-                        let foods_str = serde_json::to_string_pretty(&self.foods)
-                            .expect("Failed to serialize foods");
-                        fs::write(self.config_path.join("foods.json"), foods_str)
-                            .expect("Failed to write foods.json");
-
-                        self.foods.iter_mut().for_each(|food| {
-                            food.relevance = fuzzy_compare(
-                                &food.name.to_lowercase(),
-                                &self.search_text.to_lowercase(),
-                            ) + if food
-                                .name
-                                .to_lowercase()
-                                .contains(&self.search_text.to_lowercase())
-                            {
-                                1.0
-                            } else {
-                                0.0
-                            };
-                        });
-                        self.foods.sort_by(|a, b| {
-                            b.relevance
-                                .partial_cmp(&a.relevance)
-                                .unwrap_or(std::cmp::Ordering::Equal)
-                        });
+                        self.save_foods();
+                        self.sort_foods();
+                        self.refresh_days();
                     }
                 }
                 self.add_food_name = "".to_string();
+                self.similar_food_ids.clear();
+                self.add_food_brand = "".to_string();
+                self.add_food_aliases = "".to_string();
+                self.add_food_tags = Vec::new();
+                self.add_food_tag_input = "".to_string();
                 self.add_food_serving_size = "".to_string();
                 self.add_food_carbs = "".to_string();
                 self.add_food_fats = "".to_string();
                 self.add_food_proteins = "".to_string();
+                self.add_food_alcohol = "".to_string();
+                self.add_food_energy = "".to_string();
+                self.add_food_loaded_energy = None;
+                self.add_food_water = "".to_string();
+                self.add_food_serving_weight = "".to_string();
+                self.add_food_serving_unit = ServingUnit::Grams;
+                self.add_food_per_100 = false;
+                self.add_food_apply_to_past = false;
+            }
+            Message::AddFoodApplyToPastChanged(new_add_food_apply_to_past) => {
+                self.add_food_apply_to_past = new_add_food_apply_to_past;
+            }
+            Message::RestoreFoodRevision(revision_index) => {
+                let Some(revision) = self
+                    .food(self.next_food_id)
+                    .and_then(|food| food.revisions.get(revision_index))
+                    .cloned()
+                else {
+                    return Command::none();
+                };
+                self.fill_food_form(&revision);
+            }
+            Message::AddFoodServingWeightChanged(new_add_food_serving_weight) => {
+                self.add_food_serving_weight = new_add_food_serving_weight;
+            }
+            Message::AddFoodServingUnitChanged(new_add_food_serving_unit) => {
+                self.add_food_serving_unit = new_add_food_serving_unit;
+            }
+            Message::AddFoodPer100Toggled(new_add_food_per_100) => {
+                self.add_food_per_100 = new_add_food_per_100;
             }
             Message::AddFoodNameChanged(new_add_food_name) => {
                 self.add_food_name = new_add_food_name;
+                self.refresh_similar_foods();
+            }
+            Message::AddFoodBrandChanged(new_add_food_brand) => {
+                self.add_food_brand = new_add_food_brand;
+            }
+            Message::AddFoodAliasesChanged(new_add_food_aliases) => {
+                self.add_food_aliases = new_add_food_aliases;
+            }
+            Message::AddFoodTagInputChanged(new_add_food_tag_input) => {
+                self.add_food_tag_input = new_add_food_tag_input;
+            }
+            Message::AddFoodTagAdded => {
+                let tag = self.add_food_tag_input.trim().to_lowercase();
+                if !tag.is_empty() && !self.add_food_tags.contains(&tag) {
+                    self.add_food_tags.push(tag);
+                }
+                self.add_food_tag_input = "".to_string();
+            }
+            Message::AddFoodTagRemoved(tag) => {
+                self.add_food_tags.retain(|t| *t != tag);
+            }
+            Message::ToggleTagFilter(tag) => {
+                if !self.tag_filter.remove(&tag) {
+                    self.tag_filter.insert(tag);
+                }
             }
             Message::AddFoodCarbsChanged(new_add_food_carbs) => {
                 self.add_food_carbs = new_add_food_carbs;
@@ -285,6 +816,15 @@ impl Application for Macros {
             Message::AddFoodProteinsChanged(new_add_food_proteins) => {
                 self.add_food_proteins = new_add_food_proteins
             }
+            Message::AddFoodAlcoholChanged(new_add_food_alcohol) => {
+                self.add_food_alcohol = new_add_food_alcohol;
+            }
+            Message::AddFoodEnergyChanged(new_add_food_energy) => {
+                self.add_food_energy = new_add_food_energy;
+            }
+            Message::AddFoodWaterChanged(new_add_food_water) => {
+                self.add_food_water = new_add_food_water;
+            }
             Message::AddFoodServingSizeChanged(new_add_food_serving_size) => {
                 self.add_food_serving_size = new_add_food_serving_size;
             }
@@ -294,395 +834,3099 @@ impl Application for Macros {
                 }
             }
             Message::AddFeedEntry(food) => {
-                let amount = food.current_serving_size.parse::<f32>();
+                let amount = parse_servings(
+                    &food.current_serving_size,
+                    food.serving_weight,
+                    food.serving_unit,
+                    |number| self.i18n.parse_number(number),
+                );
 
-                if let Ok(amount) = amount {
-                    if amount < 0.0 || amount > 1000.0 {
+                if let Some(amount) = amount {
+                    if !(0.0..=1000.0).contains(&amount) {
                         return Command::none();
                     }
 
                     self.feed.push(FeedEntry {
                         id: self.next_feed_id,
-                        food_id: food.id,
+                        food_id: Some(food.id),
                         amount,
                         date: Utc::now(),
-                        is_daily_total: false,
-                        carbs: 0.0,
-                        fats: 0.0,
-                        protein: 0.0,
+                        utc_offset: Some(local_offset(Utc::now()).local_minus_utc()),
+                        quick_add: None,
                     });
                     self.next_feed_id += 1;
 
                     self.feed.sort_by_key(|entry| entry.date);
-
-                    let feed_str =
-                        serde_json::to_string_pretty(&self.feed).expect("Failed to serialize feed");
-                    fs::write(self.config_path.join("feed.json"), feed_str)
-                        .expect("Failed to write feed.json");
+                    self.refresh_days();
+                    self.save_feed();
                 }
             }
             Message::ModifyFood(food) => {
                 self.next_food_id = food.id;
                 self.add_food_name = food.name.clone();
-                self.add_food_carbs = food.carbs.to_string();
-                self.add_food_fats = food.fats.to_string();
-                self.add_food_proteins = food.protein.to_string();
-                self.add_food_serving_size = food.serving_size.clone();
+                self.refresh_similar_foods();
+                self.add_food_brand = food.brand.clone();
+                self.add_food_aliases = food.aliases.join(", ");
+                self.add_food_tags = food.tags.clone();
+                self.add_food_tag_input = "".to_string();
+                self.fill_food_form(&food.current_revision());
+                self.add_food_apply_to_past = false;
                 self.adding_food = true;
             }
             Message::DeleteFood(food) => {
-                self.foods = self
+                self.pending_delete = Some(PendingDelete::Food(food.id));
+            }
+            Message::ToggleFoodSelected(food_id) => {
+                if !self.selected_foods.remove(&food_id) {
+                    self.selected_foods.insert(food_id);
+                }
+            }
+            Message::SelectAllFoods => {
+                self.selected_foods = self
                     .foods
                     .iter()
-                    .filter(|f| f.id != food.id)
-                    .cloned()
+                    .filter(|food| self.is_food_listed(food))
+                    .map(|food| food.id)
                     .collect();
-                self.feed = self
-                    .feed
+            }
+            Message::ClearFoodSelection => {
+                self.selected_foods.clear();
+            }
+            Message::ShowArchivedToggled(show_archived) => {
+                self.show_archived = show_archived;
+            }
+            Message::BulkTagChanged(new_bulk_tag) => {
+                self.bulk_tag = new_bulk_tag;
+            }
+            Message::BulkBrandChanged(new_bulk_brand) => {
+                self.bulk_brand = new_bulk_brand;
+            }
+            Message::BulkAddTag => {
+                let tag = self.bulk_tag.trim().to_lowercase();
+                if tag.is_empty() {
+                    return Command::none();
+                }
+                for food in self.foods.iter_mut() {
+                    if self.selected_foods.contains(&food.id) && !food.tags.contains(&tag) {
+                        food.tags.push(tag.clone());
+                    }
+                }
+                self.bulk_tag = "".to_string();
+                self.save_foods();
+                self.sort_foods();
+            }
+            Message::BulkSetBrand => {
+                let brand = self.bulk_brand.trim().to_string();
+                if brand.is_empty() {
+                    return Command::none();
+                }
+                for food in self.foods.iter_mut() {
+                    if self.selected_foods.contains(&food.id) {
+                        food.brand = brand.clone();
+                    }
+                }
+                self.bulk_brand = "".to_string();
+                self.save_foods();
+                self.sort_foods();
+            }
+            Message::BulkArchive(archived) => {
+                for food in self.foods.iter_mut() {
+                    if self.selected_foods.contains(&food.id) {
+                        food.archived = archived;
+                    }
+                }
+                self.selected_foods.clear();
+                self.save_foods();
+            }
+            Message::BulkDelete => {
+                if !self.selected_foods.is_empty() {
+                    self.pending_delete = Some(PendingDelete::SelectedFoods);
+                }
+            }
+            Message::BulkExport => {
+                let selected: Vec<&Food> = self
+                    .foods
                     .iter()
-                    .filter(|f| f.food_id != food.id)
-                    .cloned()
+                    .filter(|food| self.selected_foods.contains(&food.id))
                     .collect();
-
+                let path = self.settings.export_dir().join(format!(
+                    "foods-export-{}.json",
+                    Local::now().format("%Y%m%d-%H%M%S")
+                ));
                 let foods_str =
-                    serde_json::to_string_pretty(&self.foods).expect("Failed to serialize foods");
-                fs::write(self.config_path.join("foods.json"), foods_str)
-                    .expect("Failed to write foods.json");
+                    serde_json::to_string_pretty(&selected).expect("Failed to serialize foods");
+                self.food_status = match fs::write(&path, foods_str) {
+                    Ok(()) => self.tr_args(
+                        "Exported {0} foods to {1}",
+                        &[&selected.len(), &path.display()],
+                    ),
+                    Err(error) => self.tr_args("Export failed: {0}", &[&error]),
+                };
+            }
+            Message::ToggleFavorite(food_id) => {
+                if let Some(food) = self.foods.iter_mut().find(|food| food.id == food_id) {
+                    food.favorite = !food.favorite;
+                }
+                self.save_foods();
+            }
+            Message::MergeFood(merging_food) => {
+                match merging_food {
+                    MergeFood::Cancel => {
+                        self.merging_foods = false;
+                    }
+                    MergeFood::BeginMerge => {
+                        self.merging_foods = true;
+                        self.refresh_duplicate_foods();
+                    }
+                    MergeFood::FinishMerge => {
+                        let (Some(duplicate_id), Some(survivor_id)) =
+                            (self.merge_duplicate, self.merge_survivor)
+                        else {
+                            return Command::none();
+                        };
+                        let Some(duplicate) = self.food(duplicate_id).cloned() else {
+                            return Command::none();
+                        };
+                        if duplicate_id == survivor_id || self.food(survivor_id).is_none() {
+                            return Command::none();
+                        }
 
-                let feed_str =
-                    serde_json::to_string_pretty(&self.feed).expect("Failed to serialize feed");
-                fs::write(self.config_path.join("feed.json"), feed_str)
-                    .expect("Failed to write feed.json");
+                        self.merging_foods = false;
+
+                        if let Some(survivor) =
+                            self.foods.iter_mut().find(|food| food.id == survivor_id)
+                        {
+                            survivor.favorite |= duplicate.favorite;
+                            for tag in duplicate.tags.iter() {
+                                if !survivor.tags.contains(tag) {
+                                    survivor.tags.push(tag.clone());
+                                }
+                            }
+                            // Keep the duplicate findable under its old names.
+                            for alias in std::iter::once(&duplicate.name).chain(duplicate.aliases.iter()) {
+                                if !alias.eq_ignore_ascii_case(&survivor.name)
+                                    && !survivor.aliases.iter().any(|a| a.eq_ignore_ascii_case(alias))
+                                {
+                                    survivor.aliases.push(alias.clone());
+                                }
+                            }
+                        }
+                        self.foods.retain(|food| food.id != duplicate_id);
+                        self.feed
+                            .iter_mut()
+                            .filter(|entry| entry.food_id == Some(duplicate_id))
+                            .for_each(|entry| entry.food_id = Some(survivor_id));
+                        self.templates
+                            .iter_mut()
+                            .flat_map(|template| template.items.iter_mut())
+                            .filter(|item| item.food_id == Some(duplicate_id))
+                            .for_each(|item| item.food_id = Some(survivor_id));
+
+                        self.sort_foods();
+                        self.refresh_days();
+                        self.save_foods();
+                        self.save_feed();
+                        self.save_templates();
+                    }
+                }
+                self.merge_duplicate = None;
+                self.merge_survivor = None;
+            }
+            Message::MergeDuplicateSelected(choice) => {
+                self.merge_duplicate = Some(choice.id);
+            }
+            Message::MergeSurvivorSelected(choice) => {
+                self.merge_survivor = Some(choice.id);
+            }
+            Message::MergeSuggestionSelected(duplicate_id, survivor_id) => {
+                self.merge_duplicate = Some(duplicate_id);
+                self.merge_survivor = Some(survivor_id);
             }
             Message::DeleteFeedEntry(feed_entry_id) => {
-                self.feed = self
+                self.pending_delete = Some(PendingDelete::FeedEntry(feed_entry_id));
+            }
+            Message::ConfirmDelete(confirmation) => {
+                let Some(pending_delete) = self.pending_delete.take() else {
+                    return Command::none();
+                };
+                match (confirmation, pending_delete) {
+                    (ConfirmDelete::Cancel, _) => {}
+                    (ConfirmDelete::Archive, PendingDelete::Food(food_id)) => {
+                        if let Some(food) = self.foods.iter_mut().find(|food| food.id == food_id) {
+                            food.archived = true;
+                        }
+                        self.selected_foods.remove(&food_id);
+                        self.save_foods();
+                    }
+                    (ConfirmDelete::Archive, PendingDelete::SelectedFoods) => {
+                        for food in self.foods.iter_mut() {
+                            if self.selected_foods.contains(&food.id) {
+                                food.archived = true;
+                            }
+                        }
+                        self.selected_foods.clear();
+                        self.save_foods();
+                    }
+                    (ConfirmDelete::Archive, PendingDelete::FeedEntry(_)) => {}
+                    (ConfirmDelete::Delete, PendingDelete::Food(food_id)) => {
+                        self.delete_foods(&BTreeSet::from([food_id]));
+                    }
+                    (ConfirmDelete::Delete, PendingDelete::SelectedFoods) => {
+                        let selected = std::mem::take(&mut self.selected_foods);
+                        self.delete_foods(&selected);
+                    }
+                    (ConfirmDelete::Delete, PendingDelete::FeedEntry(feed_entry_id)) => {
+                        self.feed = self
+                            .feed
+                            .iter()
+                            .filter(|f| f.id != feed_entry_id)
+                            .cloned()
+                            .collect();
+                        self.refresh_days();
+                        self.save_feed();
+                    }
+                }
+            }
+            Message::CopyFeedEntryToToday(feed_entry_id) => {
+                self.copy_to_today(&[feed_entry_id], false);
+            }
+            Message::CopyDayToToday(date) => {
+                let ids: Vec<u32> = self
+                    .days
+                    .iter()
+                    .filter(|day| day.date == date)
+                    .flat_map(|day| day.entries.iter().map(|&index| self.feed[index].id))
+                    .collect();
+                self.copy_to_today(&ids, true);
+            }
+            Message::ToggleFeedEntrySelected(feed_entry_id) => {
+                if !self.selected_feed_entries.remove(&feed_entry_id) {
+                    self.selected_feed_entries.insert(feed_entry_id);
+                }
+            }
+            Message::NewTemplateNameChanged(new_template_name) => {
+                self.new_template_name = new_template_name;
+            }
+            Message::CreateTemplate => {
+                if self.new_template_name.trim().is_empty() {
+                    return Command::none();
+                }
+                let items: Vec<TemplateItem> = self
                     .feed
                     .iter()
-                    .filter(|f| f.id != feed_entry_id)
-                    .cloned()
+                    .filter(|entry| {
+                        self.selected_feed_entries.contains(&entry.id) && !self.is_orphaned(entry)
+                    })
+                    .map(|entry| TemplateItem {
+                        food_id: entry.food_id,
+                        amount: entry.amount,
+                        quick_add: entry.quick_add.clone(),
+                        amount_input: String::new(),
+                    })
                     .collect();
+                if items.is_empty() {
+                    return Command::none();
+                }
 
-                let feed_str =
-                    serde_json::to_string_pretty(&self.feed).expect("Failed to serialize feed");
-                fs::write(self.config_path.join("feed.json"), feed_str)
-                    .expect("Failed to write feed.json");
+                let mut template = MealTemplate {
+                    id: self.next_template_id,
+                    name: self.new_template_name.trim().to_string(),
+                    items,
+                    scale: String::new(),
+                };
+                template.reset_inputs(&self.i18n);
+                self.templates.push(template);
+                self.next_template_id += 1;
+                self.selected_feed_entries.clear();
+                self.new_template_name = "".to_string();
+                self.save_templates();
             }
-        }
-
-        Command::none()
-    }
+            Message::RenameTemplate(template_id, name) => {
+                if let Some(template) = self.template_mut(template_id) {
+                    template.name = name;
+                }
+                self.save_templates();
+            }
+            Message::TemplateScaleChanged(template_id, scale) => {
+                if let Some(template) = self.template_mut(template_id) {
+                    template.scale = scale;
+                }
+            }
+            Message::TemplateItemAmountChanged(template_id, item_index, amount_input) => {
+                let amount = self.i18n.parse_number(&amount_input);
+                if let Some(item) = self
+                    .template_mut(template_id)
+                    .and_then(|template| template.items.get_mut(item_index))
+                {
+                    if let Some(amount) = amount {
+                        if (0.0..=1000.0).contains(&amount) {
+                            item.amount = amount;
+                        }
+                    }
+                    item.amount_input = amount_input;
+                }
+                self.save_templates();
+            }
+            Message::AddTemplateItem(template_id, choice) => {
+                if let Some(template) = self.template_mut(template_id) {
+                    template.items.push(TemplateItem {
+                        food_id: Some(choice.id),
+                        amount: 1.0,
+                        quick_add: None,
+                        amount_input: String::from("1"),
+                    });
+                }
+                self.save_templates();
+            }
+            Message::RemoveTemplateItem(template_id, item_index) => {
+                if let Some(template) = self.template_mut(template_id) {
+                    if item_index < template.items.len() {
+                        template.items.remove(item_index);
+                    }
+                }
+                self.save_templates();
+            }
+            Message::LogTemplate(template_id) => {
+                let Some(template) = self.templates.iter().find(|t| t.id == template_id) else {
+                    return Command::none();
+                };
+                let Some(scale) = self.i18n.parse_number(&template.scale) else {
+                    return Command::none();
+                };
+                if !(0.0..=100.0).contains(&scale) {
+                    return Command::none();
+                }
 
-    fn view(&self) -> Element<Message> {
-        Column::with_children(vec![
-            TabBar::with_tab_labels(
-                self.tabs
+                let now = Utc::now();
+                let entries: Vec<(Option<u32>, f32, Option<QuickAdd>)> = template
+                    .items
                     .iter()
-                    .enumerate()
-                    .map(|(index, tab)| (index, TabLabel::Text(tab.title.clone())))
-                    .collect(),
-                Message::ChangeTab,
-            )
-            .set_active_tab(&self.current_tab)
-            .into(),
-            self.main_content(),
-        ])
-        .into()
-    }
-
-    fn theme(&self) -> Theme {
-        Theme::default()
-    }
-}
-
-impl Macros {
-    fn main_content(&self) -> Element<Message> {
-        match self.tabs[self.current_tab].tab_type {
-            TabType::Feed => self.feed(),
-            TabType::Food => {
-                if self.adding_food {
-                    self.add_food() //.explain(Color::new(1.0, 0.0, 0.0, 1.0))
-                } else {
-                    self.food_tab()
+                    .filter(|item| {
+                        item.quick_add.is_some()
+                            || item.food_id.and_then(|id| self.food(id)).is_some()
+                    })
+                    .map(|item| (item.food_id, item.amount * scale, item.quick_add.clone()))
+                    .collect();
+                for (food_id, amount, quick_add) in entries {
+                    self.feed.push(FeedEntry {
+                        id: self.next_feed_id,
+                        food_id,
+                        amount,
+                        date: now,
+                        utc_offset: Some(local_offset(now).local_minus_utc()),
+                        quick_add,
+                    });
+                    self.next_feed_id += 1;
                 }
+                self.feed.sort_by_key(|entry| entry.date);
+                self.refresh_days();
+                self.save_feed();
             }
-        }
-    }
-
-    fn feed(&self) -> Element<Message> {
-        let mut feed = self.feed.clone();
-        let mut macro_map: HashMap<String, (f32, f32, f32)> = HashMap::new();
-        for entry in self.feed.iter() {
-            let food = self
-                .foods
-                .iter()
-                .find(|food| food.id == entry.food_id)
-                .unwrap();
-
-            let day = DateTime::<Local>::from(entry.date)
-                .format("%Y-%m-%d")
-                .to_string();
-            let def: (f32, f32, f32) = (0.0, 0.0, 0.0);
-            let daily_total = macro_map.get(&day).unwrap_or(&def);
-            macro_map.insert(
-                day,
-                (
-                    daily_total.0 + food.carbs * entry.amount,
-                    daily_total.1 + food.fats * entry.amount,
-                    daily_total.2 + food.protein * entry.amount,
+            Message::DeleteTemplate(template_id) => {
+                self.templates.retain(|template| template.id != template_id);
+                self.save_templates();
+            }
+            Message::RelinkFeedEntry(feed_entry_id, food_id) => {
+                if let Some(entry) = self.feed.iter_mut().find(|f| f.id == feed_entry_id) {
+                    entry.food_id = Some(food_id);
+                }
+                self.refresh_days();
+                self.save_feed();
+            }
+            Message::WeightInputChanged(new_weight_input) => {
+                self.weight_input = new_weight_input;
+            }
+            Message::WeightUnitChanged(unit) => {
+                self.weight_log.unit = unit;
+                self.reset_number_inputs();
+                self.save_weight_log();
+            }
+            Message::TargetRateChanged(new_target_rate_input) => {
+                self.target_rate_input = new_target_rate_input;
+                if self.target_rate_input.trim().is_empty() {
+                    self.weight_log.target_rate = None;
+                } else if let Some(rate) = self.i18n.parse_number(&self.target_rate_input) {
+                    if !(-2.0..=2.0).contains(&self.weight_log.unit.to_kg(rate)) {
+                        return Command::none();
+                    }
+                    self.weight_log.target_rate = Some(self.weight_log.unit.to_kg(rate));
+                } else {
+                    return Command::none();
+                }
+                self.save_weight_log();
+            }
+            Message::AddWeightEntry => {
+                let Some(weight) = self.i18n.parse_number(&self.weight_input) else {
+                    return Command::none();
+                };
+                let weight = self.weight_log.unit.to_kg(weight);
+                if !(1.0..=1000.0).contains(&weight) {
+                    return Command::none();
+                }
+
+                let now = Utc::now();
+                self.weight_log.entries.push(WeightEntry {
+                    id: self.next_weight_id,
+                    weight,
+                    date: now,
+                    utc_offset: Some(local_offset(now).local_minus_utc()),
+                });
+                self.next_weight_id += 1;
+                self.weight_log.entries.sort_by_key(|entry| entry.date);
+                self.weight_input = "".to_string();
+                self.save_weight_log();
+            }
+            Message::DeleteWeightEntry(weight_entry_id) => {
+                self.weight_log
+                    .entries
+                    .retain(|entry| entry.id != weight_entry_id);
+                self.save_weight_log();
+            }
+            Message::AddWater(amount) => {
+                let now = Utc::now();
+                self.water_log.entries.push(WaterEntry {
+                    id: self.next_water_id,
+                    amount,
+                    date: now,
+                    utc_offset: Some(local_offset(now).local_minus_utc()),
+                });
+                self.next_water_id += 1;
+                self.water_log.entries.sort_by_key(|entry| entry.date);
+                self.refresh_days();
+                self.save_water_log();
+            }
+            Message::DeleteWaterEntry(water_entry_id) => {
+                self.water_log
+                    .entries
+                    .retain(|entry| entry.id != water_entry_id);
+                self.refresh_days();
+                self.save_water_log();
+            }
+            Message::WaterTargetChanged(new_water_target_input) => {
+                self.water_target_input = new_water_target_input;
+                if let Some(target) = self.i18n.parse_number(&self.water_target_input) {
+                    if (0.0..=20000.0).contains(&target) {
+                        self.water_log.target = target;
+                        self.save_water_log();
+                    }
+                }
+            }
+            Message::QuickAddLabelChanged(new_quick_add_label) => {
+                self.quick_add_label = new_quick_add_label;
+            }
+            Message::QuickAddCarbsChanged(new_quick_add_carbs) => {
+                self.quick_add_carbs = new_quick_add_carbs;
+            }
+            Message::QuickAddFatsChanged(new_quick_add_fats) => {
+                self.quick_add_fats = new_quick_add_fats;
+            }
+            Message::QuickAddProteinChanged(new_quick_add_protein) => {
+                self.quick_add_protein = new_quick_add_protein;
+            }
+            Message::QuickAddAlcoholChanged(new_quick_add_alcohol) => {
+                self.quick_add_alcohol = new_quick_add_alcohol;
+            }
+            Message::QuickAdd => {
+                // Blank fields count as zero so e.g. only protein can be logged.
+                let parse = |value: &str| {
+                    if value.trim().is_empty() {
+                        Some(0.0)
+                    } else {
+                        self.i18n.parse_number(value)
+                    }
+                };
+                let (Some(carbs), Some(fats), Some(protein), Some(alcohol)) = (
+                    parse(&self.quick_add_carbs),
+                    parse(&self.quick_add_fats),
+                    parse(&self.quick_add_protein),
+                    parse(&self.quick_add_alcohol),
+                ) else {
+                    return Command::none();
+                };
+                if [carbs, fats, protein, alcohol]
+                    .iter()
+                    .any(|value| !(0.0..=1000.0).contains(value))
+                    || carbs + fats + protein + alcohol == 0.0
+                {
+                    return Command::none();
+                }
+
+                self.feed.push(FeedEntry {
+                    id: self.next_feed_id,
+                    food_id: None,
+                    amount: 1.0,
+                    date: Utc::now(),
+                    utc_offset: Some(local_offset(Utc::now()).local_minus_utc()),
+                    quick_add: Some(QuickAdd {
+                        label: self.quick_add_label.trim().to_string(),
+                        carbs,
+                        fats,
+                        protein,
+                        alcohol,
+                    }),
+                });
+                self.next_feed_id += 1;
+                self.feed.sort_by_key(|entry| entry.date);
+                self.refresh_days();
+                self.save_feed();
+
+                self.quick_add_label = "".to_string();
+                self.quick_add_carbs = "".to_string();
+                self.quick_add_fats = "".to_string();
+                self.quick_add_protein = "".to_string();
+                self.quick_add_alcohol = "".to_string();
+            }
+            Message::CalorieGoalChanged(new_calorie_goal_input) => {
+                self.calorie_goal_input = new_calorie_goal_input;
+                if self.calorie_goal_input.trim().is_empty() {
+                    self.goals.calories = None;
+                } else if let Some(goal) = self.i18n.parse_number(&self.calorie_goal_input) {
+                    let calories = self.settings.energy_unit.to_kcal(goal);
+                    if !(0.0..=20000.0).contains(&calories) {
+                        return Command::none();
+                    }
+                    self.goals.calories = Some(calories);
+                } else {
+                    return Command::none();
+                }
+                self.save_goals();
+            }
+            Message::GoalToleranceChanged(new_goal_tolerance_input) => {
+                self.goal_tolerance_input = new_goal_tolerance_input;
+                if let Some(tolerance) = self.i18n.parse_number(&self.goal_tolerance_input) {
+                    if (0.0..=100.0).contains(&tolerance) {
+                        self.goals.tolerance = tolerance;
+                        self.save_goals();
+                    }
+                }
+            }
+            Message::ApplySuggestedGoal(calories) => {
+                let calories = calories.round();
+                self.goals.calories = Some(calories);
+                self.reset_number_inputs();
+                self.save_goals();
+            }
+            Message::FeedScrolled(viewport) => {
+                self.feed_offset = viewport.absolute_offset().y;
+            }
+        }
+
+        // A deletion waiting for confirmation is dropped once its target is gone.
+        if self
+            .pending_delete
+            .is_some_and(|pending_delete| !self.pending_delete_exists(pending_delete))
+        {
+            self.pending_delete = None;
+        }
+
+        if let Some(snapshot) = undo_snapshot {
+            if snapshot.data_version != self.data_version {
+                self.undo_stack.push(snapshot);
+                if self.undo_stack.len() > UNDO_LIMIT {
+                    self.undo_stack.remove(0);
+                }
+            }
+        }
+
+        Command::none()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::batch([
+            subscription::events_with(shortcuts::keyboard_shortcut),
+            subscription::events_with(|event, _status| match event {
+                Event::Window(window::Event::Resized { width, height }) => {
+                    Some(Message::WindowResized(width, height))
+                }
+                Event::Window(window::Event::CloseRequested) => Some(Message::WindowCloseRequested),
+                _ => None,
+            }),
+        ])
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        Column::with_children(vec![
+            TabBar::with_tab_labels(
+                self.tabs
+                    .iter()
+                    .enumerate()
+                    .map(|(index, tab)| (index, TabLabel::Text(self.tr(&tab.title).to_string())))
+                    .collect(),
+                Message::ChangeTab,
+            )
+            .set_active_tab(&self.current_tab)
+            .into(),
+            if self.palette_open {
+                self.palette()
+            } else {
+                self.main_content()
+            },
+        ])
+        .into()
+    }
+
+    fn theme(&self) -> Theme {
+        self.theme.clone()
+    }
+
+    fn scale_factor(&self) -> f64 {
+        (self.settings.text_size / BASE_TEXT_SIZE) as f64
+    }
+}
+
+impl Macros {
+    /// State of the app with all data read from the data directory of `settings`, fails on
+    /// files that can't be read or parsed.
+    fn load(settings: UserSettings) -> Result<Self, String> {
+        let i18n = Catalog::new(settings.locale());
+        let config_path = settings.data_dir();
+        fs::create_dir_all(&config_path).map_err(|error| {
+            i18n.tr_args(
+                "Failed to create {0}: {1}",
+                &[&config_path.display(), &error],
+            )
+        })?;
+        let mut foods: Vec<Food> = load_data(&config_path, "foods.json", "[]", &i18n)?;
+        let next_food_id = foods.iter().fold(0, |acc, food| max(acc, food.id + 1));
+        foods
+            .iter_mut()
+            .for_each(|food| food.current_serving_size = i18n.input(1.0));
+
+        let mut feed: Vec<FeedEntry> = load_data(&config_path, "feed.json", "[]", &i18n)?;
+        let next_feed_id = feed.iter().fold(0, |acc, food| max(acc, food.id + 1));
+        let migrated_feed = sort_and_migrate(&mut feed);
+
+        let mut weight_log: WeightLog = load_data(&config_path, "weight.json", "{}", &i18n)?;
+        let next_weight_id = weight_log
+            .entries
+            .iter()
+            .fold(0, |acc, entry| max(acc, entry.id + 1));
+        let migrated_weight_log = sort_and_migrate(&mut weight_log.entries);
+        let target_rate_input = weight_log
+            .target_rate
+            .map(|rate| i18n.number(weight_log.unit.in_unit(rate), 2))
+            .unwrap_or_default();
+
+        let mut water_log: WaterLog = load_data(&config_path, "water.json", "{}", &i18n)?;
+        let next_water_id = water_log
+            .entries
+            .iter()
+            .fold(0, |acc, entry| max(acc, entry.id + 1));
+        let migrated_water_log = sort_and_migrate(&mut water_log.entries);
+        let water_target_input = i18n.input(water_log.target);
+
+        let goals: Goals = load_data(&config_path, "goals.json", "{}", &i18n)?;
+        let calorie_goal_input = goals
+            .calories
+            .map(|calories| i18n.input(settings.energy_unit.in_unit(calories).round()))
+            .unwrap_or_default();
+        let goal_tolerance_input = i18n.input(goals.tolerance);
+
+        let mut templates: Vec<MealTemplate> =
+            load_data(&config_path, "templates.json", "[]", &i18n)?;
+        let next_template_id = templates
+            .iter()
+            .fold(0, |acc, template| max(acc, template.id + 1));
+        templates
+            .iter_mut()
+            .for_each(|template| template.reset_inputs(&i18n));
+
+        let theme = settings.theme.theme();
+        let path_input = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default()
+        };
+
+        let mut macros = Macros {
+            tabs: vec![
+                Tab {
+                    title: String::from("Feed"),
+                    tab_type: TabType::Feed,
+                },
+                Tab {
+                    title: String::from("Food"),
+                    tab_type: TabType::Food,
+                },
+                Tab {
+                    title: String::from("Weight"),
+                    tab_type: TabType::Weight,
+                },
+                Tab {
+                    title: String::from("Stats"),
+                    tab_type: TabType::Stats,
+                },
+                Tab {
+                    title: String::from("Templates"),
+                    tab_type: TabType::Templates,
+                },
+                Tab {
+                    title: String::from("Integrity"),
+                    tab_type: TabType::Integrity,
+                },
+                Tab {
+                    title: String::from("Settings"),
+                    tab_type: TabType::Settings,
+                },
+            ],
+            current_tab: 0,
+            search_text: "".to_string(),
+            palette_open: false,
+            palette_query: "".to_string(),
+            theme,
+            accent_input: settings.theme.accent.clone().unwrap_or_default(),
+            window_width_input: settings.window_width.to_string(),
+            window_height_input: settings.window_height.to_string(),
+            text_size_input: i18n.input(settings.text_size),
+            precision_input: settings.precision.to_string(),
+            day_start_input: settings.day_start_hour.to_string(),
+            energy_factor_inputs: Nutrient::ALL
+                .iter()
+                .map(|nutrient| i18n.input(settings.energy_factors.get(*nutrient)))
+                .collect(),
+            data_dir_input: path_input(&settings.data_dir),
+            data_dir_error: None,
+            export_dir_input: path_input(&settings.export_dir),
+            settings,
+            i18n,
+            undo_stack: Vec::new(),
+            data_version: 0,
+            tag_filter: BTreeSet::new(),
+            show_archived: false,
+            selected_foods: BTreeSet::new(),
+            bulk_tag: "".to_string(),
+            bulk_brand: "".to_string(),
+            food_status: "".to_string(),
+            adding_food: false,
+            add_food_name: "".to_string(),
+            add_food_brand: "".to_string(),
+            add_food_aliases: "".to_string(),
+            add_food_tags: Vec::new(),
+            add_food_tag_input: "".to_string(),
+            add_food_carbs: "".to_string(),
+            add_food_serving_size: "".to_string(),
+            add_food_fats: "".to_string(),
+            add_food_proteins: "".to_string(),
+            add_food_alcohol: "".to_string(),
+            add_food_energy: "".to_string(),
+            add_food_loaded_energy: None,
+            add_food_water: "".to_string(),
+            add_food_serving_weight: "".to_string(),
+            add_food_serving_unit: ServingUnit::Grams,
+            add_food_per_100: false,
+            add_food_apply_to_past: false,
+            merging_foods: false,
+            pending_delete: None,
+            merge_duplicate: None,
+            merge_survivor: None,
+            duplicate_foods: Vec::new(),
+            similar_food_ids: Vec::new(),
+            foods,
+            food_index: HashMap::new(),
+            feed,
+            days: Vec::new(),
+            feed_offset: 0.0,
+            config_path,
+            next_food_id,
+            next_feed_id,
+            weight_log,
+            weight_input: "".to_string(),
+            target_rate_input,
+            next_weight_id,
+            water_log,
+            water_target_input,
+            quick_add_label: "".to_string(),
+            quick_add_carbs: "".to_string(),
+            quick_add_fats: "".to_string(),
+            quick_add_protein: "".to_string(),
+            quick_add_alcohol: "".to_string(),
+            next_water_id,
+            goals,
+            templates,
+            next_template_id,
+            selected_feed_entries: BTreeSet::new(),
+            new_template_name: "".to_string(),
+            calorie_goal_input,
+            goal_tolerance_input,
+        };
+        macros.sort_foods();
+        macros.refresh_days();
+        if migrated_feed {
+            macros.save_feed();
+        }
+        if migrated_weight_log {
+            macros.save_weight_log();
+        }
+        if migrated_water_log {
+            macros.save_water_log();
+        }
+        Ok(macros)
+    }
+
+    fn main_content(&self) -> Element<'_, Message> {
+        if let Some(confirmation) = self.pending_delete.and_then(|p| self.confirm_delete(p)) {
+            return confirmation;
+        }
+        match self.tabs[self.current_tab].tab_type {
+            TabType::Feed => self.feed(),
+            TabType::Food => {
+                if self.adding_food {
+                    self.add_food() //.explain(Color::new(1.0, 0.0, 0.0, 1.0))
+                } else if self.merging_foods {
+                    self.merge_foods()
+                } else {
+                    self.food_tab()
+                }
+            }
+            TabType::Weight => self.weight_tab(),
+            TabType::Stats => self.stats_tab(),
+            TabType::Templates => self.templates_tab(),
+            TabType::Integrity => self.integrity_tab(),
+            TabType::Settings => self.settings_tab(),
+        }
+    }
+
+    /// Checks every field of the add/edit food form, shown while typing.
+    fn food_form_errors(&self) -> FoodFormErrors {
+        let required = |input: &str| {
+            if input.trim().is_empty() {
+                Some(FieldError::Missing)
+            } else {
+                None
+            }
+        };
+        let number = |input: &str, optional: bool, max: f32| -> Option<FieldError> {
+            let input = input.trim();
+            if input.is_empty() {
+                return if optional {
+                    None
+                } else {
+                    Some(FieldError::Missing)
+                };
+            }
+            // "NaN" and "inf" parse as numbers too.
+            match self.i18n.parse_number(input).filter(|value| value.is_finite()) {
+                None => Some(FieldError::Invalid(self.tr("Enter a number").to_string())),
+                Some(value) if value < 0.0 => {
+                    Some(FieldError::Invalid(self.tr("Can't be negative").to_string()))
+                }
+                Some(value) if value > max => {
+                    // Rounded down so the stated maximum is itself accepted.
+                    let scale = 10f32.powi(self.settings.precision as i32);
+                    let shown_max = (max * scale).floor() / scale;
+                    Some(FieldError::Invalid(
+                        self.tr_args("Can't be more than {0}", &[&self.i18n.input(shown_max)]),
+                    ))
+                }
+                Some(_) => None,
+            }
+        };
+
+        let serving_weight = match number(
+            &self.add_food_serving_weight,
+            !self.add_food_per_100,
+            10000.0,
+        ) {
+            None if self.i18n.parse_number(&self.add_food_serving_weight) == Some(0.0) => {
+                Some(FieldError::Invalid(self.tr("Must be more than 0").to_string()))
+            }
+            error => error,
+        };
+        // Values per 100 g are limited so that a whole serving stays within bounds.
+        let per_serving = |max: f32| match self.i18n.parse_number(&self.add_food_serving_weight) {
+            Some(weight) if self.add_food_per_100 && weight > 0.0 => max * 100.0 / weight,
+            _ => max,
+        };
+
+        FoodFormErrors {
+            name: required(&self.add_food_name),
+            serving_size: required(&self.add_food_serving_size),
+            serving_weight,
+            carbs: number(&self.add_food_carbs, false, per_serving(1000.0)),
+            fats: number(&self.add_food_fats, false, per_serving(1000.0)),
+            protein: number(&self.add_food_proteins, false, per_serving(1000.0)),
+            alcohol: number(&self.add_food_alcohol, true, per_serving(1000.0)),
+            energy: number(
+                &self.add_food_energy,
+                true,
+                per_serving(self.settings.energy_unit.in_unit(20000.0)),
+            ),
+            water: number(&self.add_food_water, true, 10000.0),
+        }
+    }
+
+    /// Loads a food's nutrition values into the add/edit form.
+    fn fill_food_form(&mut self, revision: &FoodRevision) {
+        // Foods entered from a label are edited the same way.
+        let values = match (revision.per_100, revision.serving_weight) {
+            (Some(per_100), Some(serving_weight)) if revision.entered_per_100 => {
+                self.add_food_per_100 = true;
+                self.add_food_serving_weight = self.i18n.input(serving_weight);
+                per_100
+            }
+            _ => {
+                self.add_food_per_100 = false;
+                self.add_food_serving_weight = revision
+                    .serving_weight
+                    .map(|weight| self.i18n.input(weight))
+                    .unwrap_or_default();
+                Per100 {
+                    carbs: revision.carbs,
+                    fats: revision.fats,
+                    protein: revision.protein,
+                    alcohol: revision.alcohol,
+                    energy: revision.energy,
+                }
+            }
+        };
+        self.add_food_carbs = self.i18n.input(values.carbs);
+        self.add_food_fats = self.i18n.input(values.fats);
+        self.add_food_proteins = self.i18n.input(values.protein);
+        self.add_food_alcohol = if values.alcohol > 0.0 {
+            self.i18n.input(values.alcohol)
+        } else {
+            "".to_string()
+        };
+        self.add_food_energy = values
+            .energy
+            .map(|energy| self.i18n.input(self.settings.energy_unit.in_unit(energy)))
+            .unwrap_or_default();
+        self.add_food_loaded_energy = values.energy;
+        self.add_food_serving_unit = revision.serving_unit;
+        self.add_food_serving_size = revision.serving_size.clone();
+        self.add_food_water = if revision.water > 0.0 {
+            self.i18n.input(revision.water)
+        } else {
+            "".to_string()
+        };
+    }
+
+    /// Deletes foods along with every feed entry and template item that references them.
+    fn delete_foods(&mut self, food_ids: &BTreeSet<u32>) {
+        self.foods.retain(|food| !food_ids.contains(&food.id));
+        self.feed.retain(|entry| {
+            entry
+                .food_id
+                .is_none_or(|food_id| !food_ids.contains(&food_id))
+        });
+        for template in self.templates.iter_mut() {
+            template
+                .items
+                .retain(|item| item.food_id.is_none_or(|food_id| !food_ids.contains(&food_id)));
+        }
+        self.selected_foods.retain(|food_id| !food_ids.contains(food_id));
+        self.reindex_foods();
+        self.refresh_days();
+
+        self.save_foods();
+        self.save_feed();
+        self.save_templates();
+    }
+
+    /// Whether the target of a pending deletion still exists.
+    fn pending_delete_exists(&self, pending_delete: PendingDelete) -> bool {
+        match pending_delete {
+            PendingDelete::Food(food_id) => self.food(food_id).is_some(),
+            PendingDelete::SelectedFoods => self
+                .selected_foods
+                .iter()
+                .any(|food_id| self.food(*food_id).is_some()),
+            PendingDelete::FeedEntry(feed_entry_id) => {
+                self.feed.iter().any(|entry| entry.id == feed_entry_id)
+            }
+        }
+    }
+
+    /// Whether handling `message` should record an undo snapshot, confirmations only count
+    /// when they act on a pending deletion.
+    fn records_undo(&self, message: &Message) -> bool {
+        match (message, self.pending_delete) {
+            // Feed entries can't be archived.
+            (Message::ConfirmDelete(ConfirmDelete::Archive), Some(PendingDelete::FeedEntry(_))) => {
+                false
+            }
+            (Message::ConfirmDelete(_), pending_delete) => {
+                message.is_undoable()
+                    && pending_delete
+                        .is_some_and(|pending_delete| self.pending_delete_exists(pending_delete))
+            }
+            _ => message.is_undoable(),
+        }
+    }
+
+    fn show_tab(&mut self, tab_type: TabType) {
+        if let Some(index) = self.tabs.iter().position(|tab| tab.tab_type == tab_type) {
+            self.current_tab = index;
+        }
+    }
+
+    /// Switches to the food tab's list, leaving any open add or merge form.
+    fn show_food_list(&mut self) {
+        self.show_tab(TabType::Food);
+        self.adding_food = false;
+        self.merging_foods = false;
+    }
+
+    /// First food listed in the food tab for the current search and filters, `None` unless
+    /// something was searched and matched.
+    fn top_search_result(&self) -> Option<&Food> {
+        if self.search_text.trim().is_empty() {
+            return None;
+        }
+        self.foods
+            .iter()
+            .find(|food| food.relevance > 0.0 && self.is_food_listed(food))
+    }
+
+    /// Every action offered by the command palette, filtered by the palette query.
+    fn palette_actions(&self) -> Vec<(String, Message)> {
+        let mut actions: Vec<(String, Message)> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .map(|(index, tab)| {
+                (
+                    self.tr_args("Go to {0}", &[&self.tr(&tab.title)]),
+                    Message::ChangeTab(index),
+                )
+            })
+            .collect();
+        actions.extend([
+            (self.tr("Focus search").to_string(), Message::FocusSearch),
+            (self.tr("Log top search result").to_string(), Message::LogTopSearchResult),
+            (self.tr("Edit top search result").to_string(), Message::EditTopSearchResult),
+            (self.tr("Create food").to_string(), Message::AddFood(AddFood::BeginAdd)),
+            (self.tr("Merge foods").to_string(), Message::MergeFood(MergeFood::BeginMerge)),
+            (self.tr("Undo").to_string(), Message::Undo),
+            (
+                self.tr("Repeat yesterday").to_string(),
+                Message::CopyDayToToday(
+                    diary_today(self.settings.day_start_hour) - Duration::days(1),
+                ),
+            ),
+            (
+                self.tr("Quick add macros").to_string(),
+                Message::FocusInput(TabType::Feed, quick_add_input_id()),
+            ),
+            (
+                self.tr("Add weigh-in").to_string(),
+                Message::FocusInput(TabType::Weight, weight_input_id()),
+            ),
+            (
+                self.tr("Save selected entries as template").to_string(),
+                Message::FocusInput(TabType::Feed, template_name_input_id()),
+            ),
+            (self.tr("Select all foods").to_string(), Message::SelectAllFoods),
+            (self.tr("Clear food selection").to_string(), Message::ClearFoodSelection),
+            (
+                self.tr(if self.show_archived {
+                    "Hide archived foods"
+                } else {
+                    "Show archived foods"
+                })
+                .to_string(),
+                Message::ShowArchivedToggled(!self.show_archived),
+            ),
+            (self.tr("Archive selected foods").to_string(), Message::BulkArchive(true)),
+            (self.tr("Unarchive selected foods").to_string(), Message::BulkArchive(false)),
+            (self.tr("Export selected foods").to_string(), Message::BulkExport),
+            (self.tr("Delete selected foods").to_string(), Message::BulkDelete),
+        ]);
+        // The bulk inputs are only shown while foods are selected.
+        if !self.selected_foods.is_empty() {
+            actions.extend([
+                (
+                    self.tr("Add tag to selected foods").to_string(),
+                    Message::FocusInput(TabType::Food, bulk_tag_input_id()),
                 ),
+                (
+                    self.tr("Set brand of selected foods").to_string(),
+                    Message::FocusInput(TabType::Food, bulk_brand_input_id()),
+                ),
+            ]);
+        }
+        for volume in QUICK_ADD_VOLUMES {
+            actions.push((
+                self.tr_args("Add {0} ml water", &[&volume]),
+                Message::AddWater(volume),
+            ));
+        }
+        for choice in ThemeChoice::ALL {
+            actions.push((
+                self.tr_args("Use {0} theme", &[&self.tr(&choice.to_string())]),
+                Message::ThemeChoiceChanged(choice),
+            ));
+        }
+        for unit in EnergyUnit::ALL {
+            actions.push((
+                self.tr_args("Show energy in {0}", &[&unit]),
+                Message::EnergyUnitChanged(unit),
+            ));
+        }
+        for locale in Locale::ALL {
+            actions.push((
+                self.tr_args("Switch language to {0}", &[&locale]),
+                Message::LocaleChanged(Some(locale)),
+            ));
+        }
+        for template in self.templates.iter() {
+            actions.push((
+                self.tr_args("Log template {0}", &[&template.name]),
+                Message::LogTemplate(template.id),
+            ));
+        }
+
+        let query = self.palette_query.to_lowercase();
+        actions
+            .into_iter()
+            .filter(|(label, _)| {
+                let label = label.to_lowercase();
+                query.split_whitespace().all(|word| label.contains(word))
+            })
+            .collect()
+    }
+
+    fn palette(&self) -> Element<'_, Message> {
+        let actions: Vec<Element<Message>> = self
+            .palette_actions()
+            .into_iter()
+            .map(|(label, action)| {
+                Button::new(Text::new(label))
+                    .on_press(Message::PaletteAction(Box::new(action)))
+                    .width(Length::Fill)
+                    .style(theme::Button::Text)
+                    .into()
+            })
+            .collect();
+
+        Container::new(
+            Column::with_children(vec![
+                TextInput::new(self.tr("Type a command..."), &self.palette_query)
+                    .id(palette_input_id())
+                    .on_input(Message::PaletteQueryChanged)
+                    .on_submit(Message::PaletteSubmit)
+                    .into(),
+                Scrollable::new(Column::with_children(actions).spacing(2))
+                    .height(Length::Fill)
+                    .into(),
+            ])
+            .spacing(10)
+            .width(500),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(Horizontal::Center)
+        .padding(20)
+        .into()
+    }
+
+    fn save_foods(&mut self) {
+        self.data_version += 1;
+        self.refresh_similar_foods();
+        let foods_str =
+            serde_json::to_string_pretty(&self.foods).expect("Failed to serialize foods");
+        fs::write(self.config_path.join("foods.json"), foods_str)
+            .expect("Failed to write foods.json");
+    }
+
+    fn save_feed(&mut self) {
+        self.data_version += 1;
+        let feed_str = serde_json::to_string_pretty(&self.feed).expect("Failed to serialize feed");
+        fs::write(self.config_path.join("feed.json"), feed_str)
+            .expect("Failed to write feed.json");
+    }
+
+    fn save_weight_log(&self) {
+        let weight_str = serde_json::to_string_pretty(&self.weight_log)
+            .expect("Failed to serialize weight log");
+        fs::write(self.config_path.join("weight.json"), weight_str)
+            .expect("Failed to write weight.json");
+    }
+
+    fn save_water_log(&self) {
+        let water_str = serde_json::to_string_pretty(&self.water_log)
+            .expect("Failed to serialize water log");
+        fs::write(self.config_path.join("water.json"), water_str)
+            .expect("Failed to write water.json");
+    }
+
+    fn save_templates(&self) {
+        let templates_str =
+            serde_json::to_string_pretty(&self.templates).expect("Failed to serialize templates");
+        fs::write(self.config_path.join("templates.json"), templates_str)
+            .expect("Failed to write templates.json");
+    }
+
+    fn template_mut(&mut self, template_id: u32) -> Option<&mut MealTemplate> {
+        self.templates
+            .iter_mut()
+            .find(|template| template.id == template_id)
+    }
+
+    fn save_settings(&self) {
+        self.settings.save();
+    }
+
+    fn save_goals(&self) {
+        let goals_str =
+            serde_json::to_string_pretty(&self.goals).expect("Failed to serialize goals");
+        fs::write(self.config_path.join("goals.json"), goals_str)
+            .expect("Failed to write goals.json");
+    }
+
+    fn food(&self, id: u32) -> Option<&Food> {
+        self.food_index.get(&id).map(|&index| &self.foods[index])
+    }
+
+    /// Ranks foods against the search text, boosting often logged ones, or sorts them by
+    /// name when the search is empty so the list keeps a stable order.
+    fn sort_foods(&mut self) {
+        if self.search_text.trim().is_empty() {
+            self.foods.iter_mut().for_each(|food| {
+                food.relevance = 0.0;
+                food.highlight = Vec::new();
+            });
+            self.foods.sort_by(|a, b| {
+                a.name
+                    .to_lowercase()
+                    .cmp(&b.name.to_lowercase())
+                    .then(a.id.cmp(&b.id))
+            });
+        } else {
+            let mut counts: HashMap<u32, u32> = HashMap::new();
+            for food_id in self.feed.iter().filter_map(|entry| entry.food_id) {
+                *counts.entry(food_id).or_default() += 1;
+            }
+            let max_count = counts.values().copied().max().unwrap_or(0);
+
+            for food in self.foods.iter_mut() {
+                let search_match =
+                    search::score(&self.search_text, &food.name, &food.brand, &food.aliases);
+                food.relevance = search_match.score;
+                if search_match.score > 0.0 {
+                    food.relevance += search::usage_boost(
+                        counts.get(&food.id).copied().unwrap_or(0),
+                        max_count,
+                    );
+                }
+                food.highlight = search_match.highlight;
+            }
+            self.foods.sort_by(|a, b| {
+                b.relevance
+                    .partial_cmp(&a.relevance)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(a.id.cmp(&b.id))
+            });
+        }
+        self.reindex_foods();
+    }
+
+    /// Rebuilds the id to position lookup, must be called whenever `foods` is reordered.
+    fn reindex_foods(&mut self) {
+        self.food_index = self
+            .foods
+            .iter()
+            .enumerate()
+            .map(|(index, food)| (food.id, index))
+            .collect();
+    }
+
+    /// Logs the given feed entries again with fresh ids, either now or at their original
+    /// time of day if that has already passed today.
+    fn copy_to_today(&mut self, feed_entry_ids: &[u32], keep_time_of_day: bool) {
+        let now = Utc::now();
+        let day_start_hour = self.settings.day_start_hour;
+        let today = diary_today(day_start_hour);
+        let copies: Vec<FeedEntry> = self
+            .feed
+            .iter()
+            .filter(|entry| feed_entry_ids.contains(&entry.id) && !self.is_orphaned(entry))
+            .map(|entry| {
+                let date = if keep_time_of_day {
+                    // Time since the start of the entry's calendar day, which may be the
+                    // day after its diary day.
+                    let since_day = entry.local_time().naive_local()
+                        - entry.diary_date(day_start_hour).and_time(NaiveTime::MIN);
+                    Local
+                        .from_local_datetime(&(today.and_time(NaiveTime::MIN) + since_day))
+                        .earliest()
+                        .map(DateTime::<Utc>::from)
+                        .unwrap_or(now)
+                        // Entries from later in the day are logged now rather than ahead.
+                        .min(now)
+                } else {
+                    now
+                };
+                FeedEntry {
+                    date,
+                    utc_offset: Some(local_offset(date).local_minus_utc()),
+                    ..entry.clone()
+                }
+            })
+            .collect();
+        if copies.is_empty() {
+            return;
+        }
+
+        for mut copy in copies {
+            copy.id = self.next_feed_id;
+            self.next_feed_id += 1;
+            self.feed.push(copy);
+        }
+        self.feed.sort_by_key(|entry| entry.date);
+        self.refresh_days();
+        self.save_feed();
+    }
+
+    /// Carbs, fats and protein of a feed entry, `None` if its food no longer exists.
+    fn entry_macros(&self, entry: &FeedEntry) -> Option<(f32, f32, f32)> {
+        if let Some(quick_add) = &entry.quick_add {
+            return Some((
+                quick_add.carbs * entry.amount,
+                quick_add.fats * entry.amount,
+                quick_add.protein * entry.amount,
+            ));
+        }
+        let food = self.food(entry.food_id?)?;
+        let (carbs, fats, protein) = match food.revision_at(entry.date) {
+            Some(revision) => (revision.carbs, revision.fats, revision.protein),
+            None => (food.carbs, food.fats, food.protein),
+        };
+        Some((carbs * entry.amount, fats * entry.amount, protein * entry.amount))
+    }
+
+    /// Alcohol in g of a feed entry, zero for missing foods.
+    fn entry_alcohol(&self, entry: &FeedEntry) -> f32 {
+        if let Some(quick_add) = &entry.quick_add {
+            return quick_add.alcohol * entry.amount;
+        }
+        entry
+            .food_id
+            .and_then(|id| self.food(id))
+            .map(|food| {
+                let alcohol = food
+                    .revision_at(entry.date)
+                    .map_or(food.alcohol, |revision| revision.alcohol);
+                alcohol * entry.amount
+            })
+            .unwrap_or(0.0)
+    }
+
+    /// Energy of a feed entry in kcal, `None` if its food no longer exists.
+    fn entry_energy(&self, entry: &FeedEntry) -> Option<f32> {
+        let factors = &self.settings.energy_factors;
+        if let Some(quick_add) = &entry.quick_add {
+            return Some(quick_add.energy(factors) * entry.amount);
+        }
+        let food = self.food(entry.food_id?)?;
+        let energy = match food.revision_at(entry.date) {
+            Some(revision) => revision.energy(factors),
+            None => food.energy(factors),
+        };
+        Some(energy * entry.amount)
+    }
+
+    /// Refills the inputs of stored numbers, after their unit or the locale changed.
+    fn reset_number_inputs(&mut self) {
+        let i18n = &self.i18n;
+        self.text_size_input = i18n.input(self.settings.text_size);
+        self.energy_factor_inputs = Nutrient::ALL
+            .iter()
+            .map(|nutrient| i18n.input(self.settings.energy_factors.get(*nutrient)))
+            .collect();
+        self.target_rate_input = self
+            .weight_log
+            .target_rate
+            .map(|rate| i18n.number(self.weight_log.unit.in_unit(rate), 2))
+            .unwrap_or_default();
+        self.water_target_input = i18n.input(self.water_log.target);
+        self.calorie_goal_input = self
+            .goals
+            .calories
+            .map(|calories| i18n.input(self.settings.energy_unit.in_unit(calories).round()))
+            .unwrap_or_default();
+        self.goal_tolerance_input = i18n.input(self.goals.tolerance);
+    }
+
+    fn tr<'a>(&'a self, message: &'a str) -> &'a str {
+        self.i18n.tr(message)
+    }
+
+    fn tr_args(&self, message: &str, args: &[&dyn fmt::Display]) -> String {
+        self.i18n.tr_args(message, args)
+    }
+
+    /// Number with the decimal places chosen in the settings and the locale's separator.
+    fn number(&self, value: f32) -> String {
+        self.i18n.number(value, self.settings.precision)
+    }
+
+    /// Serving, macros and energy of a food as listed in the food tab.
+    fn food_summary(&self, food: &Food) -> String {
+        self.tr_args(
+            "Serving Size: {0} Carbs: {1} Fats: {2} Proteins: {3}{4} Energy: {5}",
+            &[
+                &food.serving_description(&self.i18n),
+                &self.number(food.carbs),
+                &self.number(food.fats),
+                &self.number(food.protein),
+                &self.alcohol_text(food.alcohol),
+                &self.energy_text(food.energy(&self.settings.energy_factors)),
+            ],
+        )
+    }
+
+    /// Alcohol appended to macro summaries, empty for the many foods without any.
+    fn alcohol_text(&self, alcohol: f32) -> String {
+        if alcohol > 0.0 {
+            self.tr_args(" Alcohol: {0}", &[&self.number(alcohol)])
+        } else {
+            String::new()
+        }
+    }
+
+    /// Energy in the unit chosen in the settings, e.g. "523.0 kcal".
+    fn energy_text(&self, kcal: f32) -> String {
+        let unit = self.settings.energy_unit;
+        format!("{} {}", self.number(unit.in_unit(kcal)), unit)
+    }
+
+    fn is_orphaned(&self, entry: &FeedEntry) -> bool {
+        entry.quick_add.is_none() && entry.food_id.and_then(|id| self.food(id)).is_none()
+    }
+
+    /// Recomputes the per-day totals, must be called whenever `feed`, the water log or a
+    /// food's macros change.
+    fn refresh_days(&mut self) {
+        let mut days: BTreeMap<NaiveDate, Day> = BTreeMap::new();
+        for (index, entry) in self.feed.iter().enumerate() {
+            let date = entry.diary_date(self.settings.day_start_hour);
+            let day = days.entry(date).or_insert_with(|| Day::new(date));
+            day.entries.push(index);
+            if let Some((carbs, fats, protein)) = self.entry_macros(entry) {
+                day.carbs += carbs;
+                day.fats += fats;
+                day.protein += protein;
+            }
+            day.alcohol += self.entry_alcohol(entry);
+            day.calories += self.entry_energy(entry).unwrap_or(0.0);
+            if let Some(food) = entry.food_id.and_then(|id| self.food(id)) {
+                let water = food
+                    .revision_at(entry.date)
+                    .map_or(food.water, |revision| revision.water);
+                day.water += water * entry.amount;
+            }
+        }
+        for (index, entry) in self.water_log.entries.iter().enumerate() {
+            let date = entry.diary_date(self.settings.day_start_hour);
+            let day = days.entry(date).or_insert_with(|| Day::new(date));
+            day.water_entries.push(index);
+            day.water += entry.amount;
+        }
+        self.days = days.into_values().collect();
+    }
+
+    /// Height of every feed row, fixed so the rows outside the viewport can be replaced by
+    /// empty space of the same height.
+    fn feed_row_height(&self) -> f32 {
+        // The tallest widgets of a row are its buttons and its 20 px checkbox.
+        let content = (BASE_TEXT_SIZE * LINE_HEIGHT + 2.0 * BUTTON_PADDING).max(20.0);
+        content + 2.0 * ROW_PADDING as f32
+    }
+
+    /// Whether the feed is on screen, rather than another tab, the palette or a confirmation.
+    fn feed_shown(&self) -> bool {
+        !self.palette_open
+            && self.pending_delete.is_none()
+            && matches!(self.tabs[self.current_tab].tab_type, TabType::Feed)
+    }
+
+    fn feed(&self) -> Element<'_, Message> {
+        let row_height = self.feed_row_height();
+        let day_rows = |day: &Day| 1 + day.entries.len() + day.water_entries.len();
+        let total_rows: usize = self.days.iter().map(day_rows).sum();
+        let first_row = ((self.feed_offset / row_height) as usize)
+            .saturating_sub(FEED_OVERSCAN_ROWS)
+            .min(total_rows);
+        // The feed is never taller than the window.
+        let window_height = self.settings.window_height as f32 / self.scale_factor() as f32;
+        let visible_rows = (window_height / row_height).ceil() as usize;
+        let last_row = (first_row + visible_rows + 2 * FEED_OVERSCAN_ROWS).min(total_rows);
+
+        let mut rows: Vec<Element<Message>> =
+            vec![Space::with_height(first_row as f32 * row_height).into()];
+        let mut row_index = 0;
+        for day in self.days.iter().rev() {
+            if row_index >= last_row {
+                break;
+            }
+            if row_index + day_rows(day) <= first_row {
+                row_index += day_rows(day);
+                continue;
+            }
+
+            let mut day_widgets: Vec<Element<Message>> = vec![self.daily_total_row(day)];
+            let mut entries: Vec<(DateTime<Utc>, Element<Message>)> = day
+                .entries
+                .iter()
+                .map(|&index| {
+                    let entry = &self.feed[index];
+                    (entry.date, self.feed_entry_row(entry))
+                })
+                .chain(day.water_entries.iter().map(|&index| {
+                    let entry = &self.water_log.entries[index];
+                    (entry.date, self.water_entry_row(entry))
+                }))
+                .collect();
+            entries.sort_by_key(|(date, _)| *date);
+            day_widgets.extend(entries.into_iter().rev().map(|(_, entry)| entry));
+            for row in day_widgets {
+                if (first_row..last_row).contains(&row_index) {
+                    rows.push(feed_row(row_index, row_height, row));
+                }
+                row_index += 1;
+            }
+        }
+        rows.push(Space::with_height((total_rows - last_row) as f32 * row_height).into());
+
+        let mut water_buttons: Vec<Element<Message>> =
+            vec![Text::new(self.tr("Water:")).width(60).into()];
+        for volume in QUICK_ADD_VOLUMES {
+            water_buttons.push(
+                Button::new(Text::new(format!("＋{} ml", volume)).shaping(Shaping::Advanced))
+                    .on_press(Message::AddWater(volume))
+                    .into(),
             );
         }
+        water_buttons.push(Space::new(20, 10).into());
+        water_buttons.push(Text::new(self.tr("Daily target (ml):")).into());
+        water_buttons.push(
+            TextInput::new(self.tr("Target"), &self.water_target_input)
+                .on_input(Message::WaterTargetChanged)
+                .width(100)
+                .into(),
+        );
+
+        Column::with_children(vec![
+            Row::with_children(water_buttons)
+                .align_items(Alignment::Center)
+                .spacing(10)
+                .padding(10)
+                .into(),
+            Row::with_children(vec![
+                Text::new(self.tr_args(
+                    "{0} selected, save as template:",
+                    &[&self.selected_feed_entries.len()],
+                ))
+                .width(250)
+                .into(),
+                TextInput::new(self.tr("Template Name"), &self.new_template_name)
+                    .id(template_name_input_id())
+                    .on_input(Message::NewTemplateNameChanged)
+                    .on_submit(Message::CreateTemplate)
+                    .width(200)
+                    .into(),
+                Button::new(self.tr("Save Template"))
+                    .on_press(Message::CreateTemplate)
+                    .into(),
+            ])
+            .align_items(Alignment::Center)
+            .spacing(10)
+            .padding([0, 10, 10, 10])
+            .into(),
+            Row::with_children(vec![
+                Text::new(self.tr("Quick add:")).width(80).into(),
+                TextInput::new(self.tr("Label"), &self.quick_add_label)
+                    .id(quick_add_input_id())
+                    .on_input(Message::QuickAddLabelChanged)
+                    .on_submit(Message::QuickAdd)
+                    .width(200)
+                    .into(),
+                TextInput::new(self.tr("Carbs"), &self.quick_add_carbs)
+                    .on_input(Message::QuickAddCarbsChanged)
+                    .on_submit(Message::QuickAdd)
+                    .width(80)
+                    .into(),
+                TextInput::new(self.tr("Fats"), &self.quick_add_fats)
+                    .on_input(Message::QuickAddFatsChanged)
+                    .on_submit(Message::QuickAdd)
+                    .width(80)
+                    .into(),
+                TextInput::new(self.tr("Proteins"), &self.quick_add_protein)
+                    .on_input(Message::QuickAddProteinChanged)
+                    .on_submit(Message::QuickAdd)
+                    .width(80)
+                    .into(),
+                TextInput::new(self.tr("Alcohol"), &self.quick_add_alcohol)
+                    .on_input(Message::QuickAddAlcoholChanged)
+                    .on_submit(Message::QuickAdd)
+                    .width(80)
+                    .into(),
+                Button::new(Text::new("＋").shaping(Shaping::Advanced))
+                    .on_press(Message::QuickAdd)
+                    .style(theme::Button::Positive)
+                    .into(),
+                Space::new(20, 10).into(),
+                Button::new(Text::new(self.tr("Repeat yesterday")).shaping(Shaping::Advanced))
+                    .on_press(Message::CopyDayToToday(
+                        diary_today(self.settings.day_start_hour) - Duration::days(1),
+                    ))
+                    .into(),
+            ])
+            .align_items(Alignment::Center)
+            .spacing(10)
+            .padding([0, 10, 10, 10])
+            .into(),
+            Scrollable::new(Column::with_children(rows))
+                .on_scroll(Message::FeedScrolled)
+                .into(),
+        ])
+        .into()
+    }
+
+    fn daily_total_row(&self, day: &Day) -> Element<'_, Message> {
+        Row::with_children(vec![
+            Text::new(self.i18n.date(day.date))
+                .width(300)
+                .size(20)
+                .into(),
+            Space::new(20, 10).into(),
+            Text::new("-").size(20).into(),
+            Space::new(20, 10).into(),
+            Text::new(self.tr_args(
+                "Carbs: {0} Fats: {1} Proteins: {2}{3} Energy: {4} Water: {5}/{6} ml",
+                &[
+                    &self.number(day.carbs),
+                    &self.number(day.fats),
+                    &self.number(day.protein),
+                    &self.alcohol_text(day.alcohol),
+                    &self.energy_text(day.calories),
+                    &self.i18n.number(day.water, 0),
+                    &self.i18n.number(self.water_log.target, 0),
+                ],
+            ))
+            .size(20)
+            .width(Length::Fill)
+            .into(),
+            Button::new(Text::new(self.tr("⧉ Copy to today")).shaping(Shaping::Advanced))
+                .width(Length::Shrink)
+                .on_press(Message::CopyDayToToday(day.date))
+                .into(),
+        ])
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    fn feed_entry_row(&self, feed_entry: &FeedEntry) -> Element<'_, Message> {
+        if let Some(quick_add) = &feed_entry.quick_add {
+            return self.quick_add_entry_row(feed_entry, quick_add);
+        }
+        let Some(food) = feed_entry.food_id.and_then(|id| self.food(id)) else {
+            return self.unknown_feed_entry_row(feed_entry);
+        };
+        let (carbs, fats, protein) = self.entry_macros(feed_entry).unwrap_or_default();
+
+        Row::with_children(vec![
+            Row::with_children(vec![
+                self.feed_entry_checkbox(feed_entry.id),
+                Text::new(format!(
+                    "{}",
+                    feed_entry.local_time().format("%H:%M ")
+                ))
+                .width(50)
+                .into(),
+                Text::new(&food.name).width(250).into(),
+                Space::new(20, 10).into(),
+                Text::new("-").into(),
+                Space::new(20, 10).into(),
+                Text::new(self.tr_args(
+                    "Servings: {0} Carbs: {1} Fats: {2} Proteins: {3}{4} Energy: {5}",
+                    &[
+                        &self.i18n.input(feed_entry.amount),
+                        &self.number(carbs),
+                        &self.number(fats),
+                        &self.number(protein),
+                        &self.alcohol_text(self.entry_alcohol(feed_entry)),
+                        &self.energy_text(self.entry_energy(feed_entry).unwrap_or(0.0)),
+                    ],
+                ))
+                .into(),
+            ])
+            .width(Length::FillPortion(95))
+            .into(),
+            Button::new(Text::new("⧉").shaping(Shaping::Advanced))
+                .width(Length::Shrink)
+                .on_press(Message::CopyFeedEntryToToday(feed_entry.id))
+                .into(),
+            Button::new(Text::new("🗑").shaping(Shaping::Advanced))
+                .width(Length::Shrink)
+                .on_press(Message::DeleteFeedEntry(feed_entry.id))
+                .style(theme::Button::Destructive)
+                .into(),
+        ])
+        .spacing(2.0)
+        .into()
+    }
+
+    /// Checkbox selecting an entry for creating a meal template.
+    fn feed_entry_checkbox(&self, feed_entry_id: u32) -> Element<'_, Message> {
+        Checkbox::new(
+            "",
+            self.selected_feed_entries.contains(&feed_entry_id),
+            move |_| Message::ToggleFeedEntrySelected(feed_entry_id),
+        )
+        .into()
+    }
+
+    fn quick_add_entry_row(
+        &self,
+        feed_entry: &FeedEntry,
+        quick_add: &QuickAdd,
+    ) -> Element<'_, Message> {
+        Row::with_children(vec![
+            Row::with_children(vec![
+                self.feed_entry_checkbox(feed_entry.id),
+                Text::new(format!(
+                    "{}",
+                    feed_entry.local_time().format("%H:%M ")
+                ))
+                .width(50)
+                .into(),
+                Text::new(if quick_add.label.is_empty() {
+                    self.tr("Quick Add").to_string()
+                } else {
+                    quick_add.label.clone()
+                })
+                .width(250)
+                .into(),
+                Space::new(20, 10).into(),
+                Text::new("-").into(),
+                Space::new(20, 10).into(),
+                Text::new(self.tr_args(
+                    "Servings: {0} Carbs: {1} Fats: {2} Proteins: {3}{4} Energy: {5}",
+                    &[
+                        &self.i18n.input(feed_entry.amount),
+                        &self.number(quick_add.carbs * feed_entry.amount),
+                        &self.number(quick_add.fats * feed_entry.amount),
+                        &self.number(quick_add.protein * feed_entry.amount),
+                        &self.alcohol_text(quick_add.alcohol * feed_entry.amount),
+                        &self.energy_text(
+                            quick_add.energy(&self.settings.energy_factors) * feed_entry.amount,
+                        ),
+                    ],
+                ))
+                .into(),
+            ])
+            .width(Length::FillPortion(95))
+            .into(),
+            Button::new(Text::new("⧉").shaping(Shaping::Advanced))
+                .width(Length::Shrink)
+                .on_press(Message::CopyFeedEntryToToday(feed_entry.id))
+                .into(),
+            Button::new(Text::new("🗑").shaping(Shaping::Advanced))
+                .width(Length::Shrink)
+                .on_press(Message::DeleteFeedEntry(feed_entry.id))
+                .style(theme::Button::Destructive)
+                .into(),
+        ])
+        .spacing(2.0)
+        .into()
+    }
+
+    fn water_entry_row(&self, water_entry: &WaterEntry) -> Element<'_, Message> {
+        Row::with_children(vec![
+            Row::with_children(vec![
+                Text::new(format!(
+                    "{}",
+                    water_entry.local_time().format("%H:%M ")
+                ))
+                .width(50)
+                .into(),
+                Text::new(self.tr("Water")).width(250).into(),
+                Space::new(20, 10).into(),
+                Text::new("-").into(),
+                Space::new(20, 10).into(),
+                Text::new(self.tr_args(
+                    "Volume: {0} ml",
+                    &[&self.i18n.number(water_entry.amount, 0)],
+                ))
+                .into(),
+            ])
+            .width(Length::FillPortion(95))
+            .into(),
+            Button::new(Text::new("🗑").shaping(Shaping::Advanced))
+                .width(Length::Shrink)
+                .on_press(Message::DeleteWaterEntry(water_entry.id))
+                .style(theme::Button::Destructive)
+                .into(),
+        ])
+        .into()
+    }
+
+    /// Row for an entry whose food no longer exists, offering to re-link or delete it.
+    fn unknown_feed_entry_row(&self, feed_entry: &FeedEntry) -> Element<'_, Message> {
+        let feed_entry_id = feed_entry.id;
+        Row::with_children(vec![
+            Row::with_children(vec![
+                Text::new(format!(
+                    "{}",
+                    feed_entry.local_time().format("%H:%M ")
+                ))
+                .width(50)
+                .into(),
+                Text::new(match feed_entry.food_id {
+                    Some(food_id) => self.tr_args("Unknown food (#{0})", &[&food_id]),
+                    None => self.tr("Unknown food").to_string(),
+                })
+                    .width(250)
+                    .style(self.theme.palette().danger)
+                    .into(),
+                Space::new(20, 10).into(),
+                Text::new("-").into(),
+                Space::new(20, 10).into(),
+                Text::new(self.tr_args("Servings: {0}", &[&self.i18n.input(feed_entry.amount)]))
+                    .into(),
+            ])
+            .width(Length::FillPortion(75))
+            .align_items(Alignment::Center)
+            .into(),
+            PickList::new(self.food_choices(), None, move |choice: FoodChoice| {
+                Message::RelinkFeedEntry(feed_entry_id, choice.id)
+            })
+            .placeholder(self.tr("Re-link to..."))
+            .width(Length::FillPortion(20))
+            .into(),
+            Button::new(Text::new("🗑").shaping(Shaping::Advanced))
+                .width(Length::Shrink)
+                .on_press(Message::DeleteFeedEntry(feed_entry_id))
+                .style(theme::Button::Destructive)
+                .into(),
+        ])
+        .spacing(2.0)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    fn food_choices(&self) -> Vec<FoodChoice> {
+        let mut choices: Vec<FoodChoice> = self
+            .foods
+            .iter()
+            .map(|food| FoodChoice {
+                id: food.id,
+                name: food.name.clone(),
+            })
+            .collect();
+        choices.sort_by_key(|choice| choice.name.to_lowercase());
+        choices
+    }
+
+    /// Average daily calories of each week (starting Monday) with logged food, oldest first.
+    fn weekly_calories(&self) -> Vec<(NaiveDate, f32)> {
+        let mut weeks: Vec<(NaiveDate, f32, u32)> = Vec::new();
+        for day in self.days.iter().filter(|day| !day.entries.is_empty()) {
+            let week = week_start(day.date);
+            match weeks.last_mut() {
+                Some(last) if last.0 == week => {
+                    last.1 += day.calories;
+                    last.2 += 1;
+                }
+                _ => weeks.push((week, day.calories, 1)),
+            }
+        }
+        weeks
+            .into_iter()
+            .map(|(week, calories, days)| (week, calories / days as f32))
+            .collect()
+    }
 
-        for (key, value) in macro_map {
-            let from: NaiveDateTime =
-                NaiveDateTime::parse_from_str(&format!("{} 23:59:59", key), "%Y-%m-%d %H:%M:%S")
-                    .unwrap();
-            let date_time = Local.from_local_datetime(&from).unwrap();
-            feed.push(FeedEntry {
-                id: 0,
-                food_id: 0,
-                amount: 0.0,
-                date: DateTime::<Utc>::from(date_time),
-                // %Y-%m-%d %H:%M:%S
-                is_daily_total: true,
-                carbs: value.0,
-                fats: value.1,
-                protein: value.2,
-            });
+    fn weight_tab(&self) -> Element<'_, Message> {
+        let unit = self.weight_log.unit;
+
+        let mut weeks: BTreeMap<NaiveDate, (Option<f32>, Option<f32>)> = BTreeMap::new();
+        for (week, calories) in self.weekly_calories() {
+            weeks.entry(week).or_default().0 = Some(calories);
+        }
+        let weekly_trend = self.weight_log.weekly_trend(self.settings.day_start_hour);
+        for pair in weekly_trend.windows(2) {
+            let weeks_between = (pair[1].0 - pair[0].0).num_days() as f32 / 7.0;
+            weeks.entry(pair[1].0).or_default().1 = Some((pair[1].1 - pair[0].1) / weeks_between);
         }
 
-        feed.sort_by_key(|entry| entry.date);
+        let mut rows: Vec<Element<Message>> = vec![
+            Row::with_children(vec![
+                Text::new(self.tr("Weight:")).width(100).into(),
+                TextInput::new(self.tr("Enter Weight"), &self.weight_input)
+                    .id(weight_input_id())
+                    .on_input(Message::WeightInputChanged)
+                    .on_submit(Message::AddWeightEntry)
+                    .width(200)
+                    .into(),
+                PickList::new(&WeightUnit::ALL[..], Some(unit), Message::WeightUnitChanged)
+                    .into(),
+                Button::new(Text::new("＋").shaping(Shaping::Advanced))
+                    .on_press(Message::AddWeightEntry)
+                    .style(theme::Button::Positive)
+                    .into(),
+            ])
+            .align_items(Alignment::Center)
+            .spacing(15)
+            .padding(10)
+            .into(),
+            Text::new(self.tr("Maintenance")).size(20).into(),
+            self.maintenance_estimate(),
+            Text::new(self.tr("Weekly")).size(20).into(),
+        ];
+
+        for (week, (calories, rate)) in weeks.iter().rev() {
+            rows.push(striped_row(
+                rows.len(),
+                Row::with_children(vec![
+                    Text::new(self.tr_args("Week of {0}", &[&self.i18n.date(*week)]))
+                        .width(300)
+                        .into(),
+                    Text::new(match calories {
+                        Some(calories) => {
+                            self.tr_args("Avg Energy: {0}", &[&self.energy_text(*calories)])
+                        }
+                        None => self.tr("Avg Energy: -").to_string(),
+                    })
+                    .width(200)
+                    .into(),
+                    Text::new(match rate {
+                        Some(rate) => self.tr_args(
+                            "Change: {0} {1}/week",
+                            &[&self.i18n.signed_number(unit.in_unit(*rate), 2), &unit],
+                        ),
+                        None => self.tr("Change: -").to_string(),
+                    })
+                    .into(),
+                ])
+                .into(),
+            ));
+        }
 
-        Scrollable::new(Column::with_children(
-            feed.iter()
-                .rev()
-                .enumerate()
-                .map(|(index, feed_entry)| {
-                    Container::new(if feed_entry.is_daily_total {
-                        Row::with_children(vec![
-                            Text::new(format!(
-                                "{}",
-                                DateTime::<Local>::from(feed_entry.date).format("%Y-%m-%d")
-                            ))
+        rows.push(Text::new(self.tr("Weigh-ins")).size(20).into());
+        for (entry, trend) in self
+            .weight_log
+            .entries
+            .iter()
+            .zip(self.weight_log.trend())
+            .rev()
+        {
+            rows.push(striped_row(
+                rows.len(),
+                Row::with_children(vec![
+                    Row::with_children(vec![
+                        Text::new(self.i18n.date_time(&entry.local_time()))
                             .width(300)
-                            .size(20)
                             .into(),
-                            Space::new(20, 10).into(),
-                            Text::new("-").size(20).into(),
-                            Space::new(20, 10).into(),
-                            Text::new(format!(
-                                "Carbs: {:.1} Fats: {:.1} Proteins: {:.1} Calories: {:.1}",
-                                feed_entry.carbs,
-                                feed_entry.fats,
-                                feed_entry.protein,
-                                feed_entry.carbs * 4.0
-                                    + feed_entry.fats * 9.0
-                                    + feed_entry.protein * 4.0
-                            ))
-                            .size(20)
-                            .into(),
-                        ])
-                    } else {
-                        let food = self
-                            .foods
-                            .iter()
-                            .find(|food| food.id == feed_entry.food_id)
-                            .unwrap();
+                        Text::new(self.tr_args(
+                            "Weight: {0} {1} Trend: {2} {3}",
+                            &[
+                                &self.i18n.number(unit.in_unit(entry.weight), 1),
+                                &unit,
+                                &self.i18n.number(unit.in_unit(trend), 1),
+                                &unit,
+                            ],
+                        ))
+                        .into(),
+                    ])
+                    .width(Length::FillPortion(95))
+                    .into(),
+                    Button::new(Text::new("🗑").shaping(Shaping::Advanced))
+                        .width(Length::Shrink)
+                        .on_press(Message::DeleteWeightEntry(entry.id))
+                        .style(theme::Button::Destructive)
+                        .into(),
+                ])
+                .into(),
+            ));
+        }
 
-                        Row::with_children(vec![
-                            Row::with_children(vec![
-                                Text::new(format!(
-                                "{}",
-                                DateTime::<Local>::from(feed_entry.date).format("%H:%M ")
-                            )).width(50).into(),
-                                Text::new(&food.name).width(250).into(),
-                                Space::new(20, 10).into(),
-                                Text::new("-").into(),
-                                Space::new(20, 10).into(),
-                                Text::new(format!(
-                                    "Servings: {} Carbs: {:.1} Fats: {:.1} Proteins: {:.1} Calories: {:.1}",
-                                    feed_entry.amount,
-                                    food.carbs * feed_entry.amount,
-                                    food.fats * feed_entry.amount,
-                                    food.protein * feed_entry.amount,
-                                    food.calories() * feed_entry.amount
-                                ))
-                                .into(),
-                            ])
-                            .width(Length::FillPortion(95))
+        Scrollable::new(Column::with_children(rows).spacing(5)).into()
+    }
+
+    fn maintenance_estimate(&self) -> Element<'_, Message> {
+        let unit = self.weight_log.unit;
+        let intake: Vec<(NaiveDate, f32)> = self
+            .days
+            .iter()
+            .filter(|day| !day.entries.is_empty())
+            .map(|day| (day.date, day.calories))
+            .collect();
+        let estimate = tdee::estimate(
+            &intake,
+            &self.weight_log.daily_weights(self.settings.day_start_hour),
+            diary_today(self.settings.day_start_hour),
+        );
+
+        let Some(estimate) = estimate else {
+            return Container::new(Text::new(self.tr_args(
+                "Not enough data yet, log food and weigh in regularly for at least a week of the last {0} days.",
+                &[&tdee::WINDOW_DAYS],
+            )))
+            .padding(10)
+            .into();
+        };
+
+        let mut target_row: Vec<Element<Message>> = vec![
+            Text::new(self.tr_args("Target change ({0}/week):", &[&unit]))
+                .width(200)
+                .into(),
+            TextInput::new(self.tr("e.g. -0.5"), &self.target_rate_input)
+                .on_input(Message::TargetRateChanged)
+                .width(100)
+                .into(),
+        ];
+        if let Some(rate) = self.weight_log.target_rate {
+            let goal = estimate.goal_for_rate(rate);
+            target_row.push(
+                Text::new(self.tr_args(
+                    "Suggested goal: {0} {1}/day",
+                    &[
+                        &self.i18n.number(self.settings.energy_unit.in_unit(goal), 0),
+                        &self.settings.energy_unit,
+                    ],
+                ))
+                .into(),
+            );
+            target_row.push(
+                Button::new(self.tr("Use as goal"))
+                    .on_press(Message::ApplySuggestedGoal(goal))
+                    .into(),
+            );
+        }
+
+        Column::with_children(vec![
+            Text::new(self.tr_args(
+                "Estimated maintenance: {0} ± {1} {2}/day (confidence: {3}, {4} of {5} days logged, {6} weigh-ins)",
+                &[
+                    &self.i18n.number(self.settings.energy_unit.in_unit(estimate.tdee), 0),
+                    &self.i18n.number(self.settings.energy_unit.in_unit(estimate.error), 0),
+                    &self.settings.energy_unit,
+                    &self.tr(&estimate.confidence.to_string()),
+                    &estimate.logged_days,
+                    &tdee::WINDOW_DAYS,
+                    &estimate.weigh_ins,
+                ],
+            ))
+            .into(),
+            Row::with_children(target_row)
+                .align_items(Alignment::Center)
+                .spacing(15)
+                .into(),
+        ])
+        .spacing(10)
+        .padding(10)
+        .into()
+    }
+
+    fn stats_tab(&self) -> Element<'_, Message> {
+        let today = diary_today(self.settings.day_start_hour);
+        let logged: Vec<NaiveDate> = self
+            .days
+            .iter()
+            .filter(|day| !day.entries.is_empty())
+            .map(|day| day.date)
+            .collect();
+        let within_goal: Vec<NaiveDate> = self
+            .days
+            .iter()
+            .filter(|day| !day.entries.is_empty() && self.goals.is_within(day.calories))
+            .map(|day| day.date)
+            .collect();
+        let logging_streak = streak(&logged, today);
+        let goal_streak = streak(&within_goal, today);
+
+        let mut rows: Vec<Element<Message>> = vec![
+            Row::with_children(vec![
+                Text::new(self.tr_args("Energy goal ({0}):", &[&self.settings.energy_unit]))
+                    .width(150)
+                    .into(),
+                TextInput::new(self.tr("Goal"), &self.calorie_goal_input)
+                    .on_input(Message::CalorieGoalChanged)
+                    .width(100)
+                    .into(),
+                Text::new(self.tr("Tolerance (%):")).width(150).into(),
+                TextInput::new("%", &self.goal_tolerance_input)
+                    .on_input(Message::GoalToleranceChanged)
+                    .width(100)
+                    .into(),
+            ])
+            .align_items(Alignment::Center)
+            .spacing(15)
+            .into(),
+            Text::new(self.tr_args(
+                "Logging streak: {0} days (longest: {1} days)",
+                &[&logging_streak.current, &logging_streak.longest],
+            ))
+            .size(20)
+            .into(),
+            Text::new(if self.goals.calories.is_some() {
+                self.tr_args(
+                    "Within goal streak: {0} days (longest: {1} days)",
+                    &[&goal_streak.current, &goal_streak.longest],
+                )
+            } else {
+                self.tr("Set an energy goal to track days within goal.").to_string()
+            })
+            .size(20)
+            .into(),
+        ];
+
+        for month in monthly_adherence(&logged, &within_goal, today) {
+            rows.push(striped_row(
+                rows.len(),
+                Row::with_children(vec![
+                    Text::new(self.i18n.month(month.month))
+                        .width(150)
+                        .into(),
+                    Text::new(self.tr_args(
+                        "Logged: {0}/{1} days ({2}%)",
+                        &[
+                            &month.logged,
+                            &month.days,
+                            &self.i18n.number(month.logged_percent(), 0),
+                        ],
+                    ))
+                    .width(300)
+                    .into(),
+                    Text::new(self.tr_args(
+                        "Within goal: {0}/{1} days ({2}%)",
+                        &[
+                            &month.within_goal,
+                            &month.days,
+                            &self.i18n.number(month.within_goal_percent(), 0),
+                        ],
+                    ))
+                    .into(),
+                ])
+                .into(),
+            ));
+        }
+
+        Scrollable::new(Column::with_children(rows).spacing(10).padding(10)).into()
+    }
+
+    fn templates_tab(&self) -> Element<'_, Message> {
+        let mut rows: Vec<Element<Message>> = Vec::new();
+        if self.templates.is_empty() {
+            rows.push(
+                Text::new(self.tr("Select entries in the feed and save them as a template.")).into(),
+            );
+        }
+
+        for template in self.templates.iter() {
+            let template_id = template.id;
+            let mut items: Vec<Element<Message>> = vec![Row::with_children(vec![
+                TextInput::new(self.tr("Template Name"), &template.name)
+                    .on_input(move |name| Message::RenameTemplate(template_id, name))
+                    .width(300)
+                    .into(),
+                Space::new(Length::Fill, 10).into(),
+                Text::new(self.tr("Scale:")).into(),
+                TextInput::new("1.0", &template.scale)
+                    .on_input(move |scale| Message::TemplateScaleChanged(template_id, scale))
+                    .on_submit(Message::LogTemplate(template_id))
+                    .width(60)
+                    .into(),
+                Button::new(Text::new(self.tr("＋ Log")).shaping(Shaping::Advanced))
+                    .on_press(Message::LogTemplate(template_id))
+                    .style(theme::Button::Positive)
+                    .into(),
+                Button::new(Text::new("🗑").shaping(Shaping::Advanced))
+                    .on_press(Message::DeleteTemplate(template_id))
+                    .style(theme::Button::Destructive)
+                    .into(),
+            ])
+            .align_items(Alignment::Center)
+            .spacing(5)
+            .into()];
+
+            for (item_index, item) in template.items.iter().enumerate() {
+                let name = match (&item.quick_add, item.food_id.and_then(|id| self.food(id))) {
+                    (Some(quick_add), _) if !quick_add.label.is_empty() => quick_add.label.clone(),
+                    (Some(_), _) => self.tr("Quick Add").to_string(),
+                    (None, Some(food)) => food.name.clone(),
+                    (None, None) => self.tr("Unknown food").to_string(),
+                };
+                items.push(
+                    Row::with_children(vec![
+                        Space::new(20, 10).into(),
+                        Text::new(name).width(300).into(),
+                        Text::new(self.tr("Servings:")).into(),
+                        TextInput::new("1.0", &item.amount_input)
+                            .on_input(move |amount| {
+                                Message::TemplateItemAmountChanged(template_id, item_index, amount)
+                            })
+                            .width(60)
                             .into(),
-                            Button::new(Text::new("🗑").shaping(Shaping::Advanced))
-                                .width(Length::Shrink)
-                                .on_press(Message::DeleteFeedEntry(feed_entry.id))
-                                .style(theme::Button::Destructive)
-                                .into(),
-                        ])
+                        Button::new(Text::new("✕").shaping(Shaping::Advanced))
+                            .on_press(Message::RemoveTemplateItem(template_id, item_index))
+                            .style(theme::Button::Text)
+                            .into(),
+                    ])
+                    .align_items(Alignment::Center)
+                    .spacing(5)
+                    .into(),
+                );
+            }
+            items.push(
+                Row::with_children(vec![
+                    Space::new(20, 10).into(),
+                    PickList::new(self.food_choices(), None, move |choice: FoodChoice| {
+                        Message::AddTemplateItem(template_id, choice)
                     })
-                    .width(Length::Fill)
-                    .padding(10)
-                    .style(move |_theme: &Theme| {
-                        if index % 2 == 0 {
-                            Appearance {
-                                text_color: None,
-                                background: Some(Background::Color(Color::new(0.9, 0.9, 0.9, 1.0))),
-                                border_radius: Default::default(),
-                                border_width: 0.0,
-                                border_color: Default::default(),
-                            }
-                        } else {
-                            Appearance {
+                    .placeholder(self.tr("Add Food..."))
+                    .width(300)
+                    .into(),
+                ])
+                .into(),
+            );
+
+            rows.push(striped_row(
+                rows.len(),
+                Column::with_children(items).spacing(5).into(),
+            ));
+        }
+
+        Scrollable::new(Column::with_children(rows).padding(10)).into()
+    }
+
+    fn integrity_tab(&self) -> Element<'_, Message> {
+        let orphaned: Vec<&FeedEntry> = self
+            .feed
+            .iter()
+            .filter(|entry| self.is_orphaned(entry))
+            .collect();
+
+        let mut rows: Vec<Element<Message>> = vec![Container::new(
+            Text::new(if orphaned.is_empty() {
+                self.tr("No problems found.").to_string()
+            } else {
+                self.tr_args(
+                    "{0} feed entries reference foods that no longer exist.",
+                    &[&orphaned.len()],
+                )
+            })
+            .size(20),
+        )
+        .padding(10)
+        .into()];
+        for entry in orphaned.iter().rev() {
+            rows.push(striped_row(
+                rows.len(),
+                Row::with_children(vec![
+                    Text::new(self.i18n.date(entry.diary_date(self.settings.day_start_hour)))
+                    .width(100)
+                    .into(),
+                    self.unknown_feed_entry_row(entry),
+                ])
+                .align_items(Alignment::Center)
+                .into(),
+            ));
+        }
+
+        Scrollable::new(Column::with_children(rows)).into()
+    }
+
+    fn settings_tab(&self) -> Element<'_, Message> {
+        let choices: Vec<Element<Message>> = ThemeChoice::ALL
+            .iter()
+            .map(|choice| {
+                Radio::new(
+                    self.tr(&choice.to_string()),
+                    *choice,
+                    Some(self.settings.theme.choice),
+                    Message::ThemeChoiceChanged,
+                )
+                .into()
+            })
+            .collect();
+
+        let mut accents: Vec<Element<Message>> = vec![Button::new(Text::new(self.tr("Default")))
+            .on_press(Message::AccentSelected(None))
+            .style(theme::Button::Secondary)
+            .into()];
+        for (name, hex) in ACCENT_PRESETS {
+            let color = themes::parse_hex(hex).unwrap_or_default();
+            accents.push(
+                Button::new(
+                    Row::with_children(vec![
+                        Container::new(Space::new(14, 14))
+                            .style(move |_theme: &Theme| Appearance {
                                 text_color: None,
-                                background: Some(Background::Color(Color::new(
-                                    0.95, 0.95, 0.95, 1.0,
-                                ))),
-                                border_radius: Default::default(),
+                                background: Some(Background::Color(color)),
+                                border_radius: 7.0.into(),
                                 border_width: 0.0,
                                 border_color: Default::default(),
-                            }
-                        }
+                            })
+                            .into(),
+                        Text::new(self.tr(name)).into(),
+                    ])
+                    .spacing(5)
+                    .align_items(Alignment::Center),
+                )
+                .on_press(Message::AccentSelected(Some(hex.to_string())))
+                .style(theme::Button::Secondary)
+                .into(),
+            );
+        }
+
+        Scrollable::new(Column::with_children(vec![
+            section_header(self.tr("Window")),
+            Column::with_children(vec![
+                setting_row(self.tr("Width:"), "px", &self.window_width_input, Message::WindowWidthChanged),
+                setting_row(self.tr("Height:"), "px", &self.window_height_input, Message::WindowHeightChanged),
+                setting_row(self.tr("Text size:"), "px", &self.text_size_input, Message::TextSizeChanged),
+            ])
+            .spacing(5)
+            .padding([0, 10])
+            .into(),
+            section_header(self.tr("Display")),
+            Column::with_children(vec![
+                setting_row(
+                    self.tr("Decimal places:"),
+                    "0-4",
+                    &self.precision_input,
+                    Message::PrecisionChanged,
+                ),
+                setting_row(
+                    self.tr("Day starts at (hour):"),
+                    "0-23",
+                    &self.day_start_input,
+                    Message::DayStartChanged,
+                ),
+            ])
+            .spacing(5)
+            .padding([0, 10])
+            .into(),
+            section_header(self.tr("Energy")),
+            Column::with_children(vec![
+                Row::with_children(
+                    EnergyUnit::ALL
+                        .iter()
+                        .map(|unit| {
+                            Radio::new(
+                                unit.to_string(),
+                                *unit,
+                                Some(self.settings.energy_unit),
+                                Message::EnergyUnitChanged,
+                            )
+                            .into()
+                        })
+                        .collect(),
+                )
+                .spacing(20)
+                .into(),
+                Row::with_children(
+                    Nutrient::ALL
+                        .iter()
+                        .zip(self.energy_factor_inputs.iter())
+                        .map(|(nutrient, input)| {
+                            let nutrient = *nutrient;
+                            Row::with_children(vec![
+                                Text::new(self.tr_args("{0} (kcal/g):", &[&self.tr(&nutrient.to_string())])).into(),
+                                TextInput::new(self.tr("kcal/g"), input)
+                                    .on_input(move |input| Message::EnergyFactorChanged(nutrient, input))
+                                    .width(60)
+                                    .into(),
+                            ])
+                            .spacing(5)
+                            .align_items(Alignment::Center)
+                            .into()
+                        })
+                        .collect(),
+                )
+                .spacing(20)
+                .into(),
+            ])
+            .spacing(10)
+            .padding([0, 10])
+            .into(),
+            section_header(self.tr("Files")),
+            Column::with_children(vec![
+                labelled_setting(
+                    self.tr("Data directory:"),
+                    TextInput::new("~/.macros", &self.data_dir_input)
+                        .on_input(Message::DataDirChanged)
+                        .on_submit(Message::DataDirSubmitted),
+                ),
+                labelled_setting(
+                    self.tr("Export directory:"),
+                    TextInput::new(self.tr("Data directory"), &self.export_dir_input)
+                        .on_input(Message::ExportDirChanged)
+                        .on_submit(Message::ExportDirSubmitted),
+                ),
+                match &self.data_dir_error {
+                    Some(error) => Text::new(error)
+                        .style(self.theme.palette().danger)
+                        .into(),
+                    None => Text::new(self.tr("Press Enter to switch directories."))
+                        .style(themes::muted(&self.theme))
+                        .into(),
+                },
+            ])
+            .spacing(5)
+            .padding([0, 10])
+            .into(),
+            section_header(self.tr("Language")),
+            Row::with_children(
+                std::iter::once(None)
+                    .chain(Locale::ALL.into_iter().map(Some))
+                    .map(|locale| {
+                        let label = match locale {
+                            Some(locale) => locale.to_string(),
+                            None => self.tr("System").to_string(),
+                        };
+                        Radio::new(label, locale, Some(self.settings.locale), Message::LocaleChanged)
+                            .into()
+                    })
+                    .collect(),
+            )
+            .spacing(20)
+            .padding([0, 10])
+            .into(),
+            section_header(self.tr("Theme")),
+            Row::with_children(choices)
+                .spacing(20)
+                .padding([0, 10])
+                .into(),
+            section_header(self.tr("Accent color")),
+            Row::with_children(accents)
+                .spacing(10)
+                .padding([0, 10])
+                .into(),
+            Row::with_children(vec![
+                Text::new(self.tr("Custom:")).into(),
+                TextInput::new("#rrggbb", &self.accent_input)
+                    .on_input(Message::AccentInputChanged)
+                    .width(100)
+                    .into(),
+            ])
+            .spacing(10)
+            .padding(10)
+            .align_items(Alignment::Center)
+            .into(),
+        ]))
+        .into()
+    }
+
+    /// Whether a food passes the archive and tag filters of the food tab.
+    fn is_food_listed(&self, food: &Food) -> bool {
+        (self.show_archived || !food.archived)
+            && self.tag_filter.iter().all(|tag| food.tags.contains(tag))
+    }
+
+    fn food_tab(&self) -> Element<'_, Message> {
+        let matches_tags = |food: &&Food| self.is_food_listed(food);
+
+        let mut rows: Vec<Element<Message>> = Vec::new();
+        if self.search_text.is_empty() {
+            let favorites: Vec<&Food> = self
+                .foods
+                .iter()
+                .filter(|food| food.favorite)
+                .filter(matches_tags)
+                .collect();
+            let recent: Vec<&Food> = self
+                .recent_food_ids()
+                .into_iter()
+                .filter_map(|id| self.food(id))
+                .filter(matches_tags)
+                .collect();
+            let frequent: Vec<&Food> = self
+                .frequent_food_ids()
+                .into_iter()
+                .filter_map(|id| self.food(id))
+                .filter(matches_tags)
+                .collect();
+
+            for (title, foods) in [
+                ("Favorites", favorites),
+                ("Recent", recent),
+                ("Frequent", frequent),
+            ] {
+                if foods.is_empty() {
+                    continue;
+                }
+                rows.push(section_header(self.tr(title)));
+                for food in foods {
+                    rows.push(striped_row(rows.len(), self.food_row(food)));
+                }
+            }
+            if !rows.is_empty() {
+                rows.push(section_header(self.tr("All Foods")));
+            }
+        }
+        for food in self.foods.iter().filter(matches_tags) {
+            rows.push(striped_row(rows.len(), self.food_row(food)));
+        }
+
+        let mut tags: BTreeSet<&String> =
+            self.foods.iter().flat_map(|food| food.tags.iter()).collect();
+        tags.extend(self.tag_filter.iter());
+        let tag_chips: Vec<Element<Message>> = tags
+            .into_iter()
+            .map(|tag| {
+                Button::new(Text::new(tag.as_str()).size(14))
+                    .on_press(Message::ToggleTagFilter(tag.clone()))
+                    .style(if self.tag_filter.contains(tag) {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Secondary
                     })
                     .into()
+            })
+            .collect();
+
+        let mut tag_row = tag_chips;
+        tag_row.push(Space::new(Length::Fill, 10).into());
+        tag_row.push(
+            Checkbox::new(self.tr("Show archived"), self.show_archived, Message::ShowArchivedToggled)
+                .into(),
+        );
+
+        Column::with_children(vec![
+            TextInput::new(self.tr("Find Food..."), &self.search_text)
+                .id(search_input_id())
+                .on_input(Message::ChangeSearchText)
+                .on_submit(Message::LogTopSearchResult)
+                .into(),
+            Row::with_children(tag_row)
+                .spacing(5)
+                .padding(5)
+                .align_items(Alignment::Center)
+                .into(),
+            self.bulk_actions(),
+            FloatingElement::new(
+                Scrollable::new(Column::with_children(rows))
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+                Row::with_children(vec![
+                    Button::new(Text::new(self.tr("Merge Foods")).shaping(Shaping::Advanced))
+                        .on_press(Message::MergeFood(MergeFood::BeginMerge))
+                        .into(),
+                    Button::new(Text::new(self.tr("Create Food")).shaping(Shaping::Advanced))
+                        .on_press(Message::AddFood(AddFood::BeginAdd))
+                        .into(),
+                ])
+                .spacing(10),
+            )
+            .anchor(Anchor::SouthEast)
+            .offset(Offset::from(20.0))
+            .into(),
+        ])
+        .into()
+    }
+
+    fn bulk_actions(&self) -> Element<'_, Message> {
+        if self.selected_foods.is_empty() {
+            return Text::new(&self.food_status).into();
+        }
+
+        Column::with_children(vec![
+            Row::with_children(vec![
+                Text::new(self.tr_args("{0} selected", &[&self.selected_foods.len()]))
+                    .width(100)
+                    .into(),
+                Button::new(self.tr("Select All"))
+                    .on_press(Message::SelectAllFoods)
+                    .style(theme::Button::Secondary)
+                    .into(),
+                Button::new(self.tr("Clear"))
+                    .on_press(Message::ClearFoodSelection)
+                    .style(theme::Button::Secondary)
+                    .into(),
+                Space::new(20, 10).into(),
+                TextInput::new(self.tr("Tag"), &self.bulk_tag)
+                    .id(bulk_tag_input_id())
+                    .on_input(Message::BulkTagChanged)
+                    .on_submit(Message::BulkAddTag)
+                    .width(120)
+                    .into(),
+                Button::new(self.tr("Add Tag")).on_press(Message::BulkAddTag).into(),
+                TextInput::new(self.tr("Brand"), &self.bulk_brand)
+                    .id(bulk_brand_input_id())
+                    .on_input(Message::BulkBrandChanged)
+                    .on_submit(Message::BulkSetBrand)
+                    .width(120)
+                    .into(),
+                Button::new(self.tr("Set Brand")).on_press(Message::BulkSetBrand).into(),
+                Space::new(20, 10).into(),
+                Button::new(self.tr("Archive")).on_press(Message::BulkArchive(true)).into(),
+                Button::new(self.tr("Unarchive"))
+                    .on_press(Message::BulkArchive(false))
+                    .into(),
+                Button::new(self.tr("Export")).on_press(Message::BulkExport).into(),
+                Button::new(self.tr("Delete"))
+                    .on_press(Message::BulkDelete)
+                    .style(theme::Button::Destructive)
+                    .into(),
+            ])
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .into(),
+            Text::new(&self.food_status).into(),
+        ])
+        .padding(5)
+        .into()
+    }
+
+    fn food_row<'a>(&'a self, food: &'a Food) -> Element<'a, Message> {
+        let food_id = food.id;
+        Row::with_children(vec![
+            Row::with_children(vec![
+                Checkbox::new("", self.selected_foods.contains(&food_id), move |_| {
+                    Message::ToggleFoodSelected(food_id)
                 })
-                .collect(),
-        ))
+                .into(),
+                Button::new(Text::new(if food.favorite { "★" } else { "☆" }).shaping(Shaping::Advanced))
+                    .on_press(Message::ToggleFavorite(food.id))
+                    .style(theme::Button::Text)
+                    .into(),
+                highlighted_name(food, &self.theme, &self.i18n),
+                Space::new(20, 10).into(),
+                Text::new("-").into(),
+                Space::new(20, 10).into(),
+                Text::new(self.food_summary(food))
+                .into(),
+            ])
+            .width(Length::FillPortion(5))
+            .align_items(Alignment::Center)
+            .into(),
+            Row::with_children(vec![
+                TextInput::new(
+                    self.tr(if food.serving_weight.is_some() {
+                        "Servings or Weight"
+                    } else {
+                        "Servings"
+                    }),
+                    &food.current_serving_size,
+                )
+                .on_input(|new_str| Message::FoodServingCurrentServingSizeChanged(food.id, new_str))
+                .into(),
+                Button::new(Text::new("＋").shaping(Shaping::Advanced))
+                    .on_press(Message::AddFeedEntry(food.clone()))
+                    .style(theme::Button::Positive)
+                    .into(),
+                Button::new(Text::new("⚙").shaping(Shaping::Advanced))
+                    .on_press(Message::ModifyFood(food.clone()))
+                    .into(),
+                Button::new(Text::new("🗑").shaping(Shaping::Advanced))
+                    .on_press(Message::DeleteFood(food.clone()))
+                    .style(theme::Button::Destructive)
+                    .into(),
+            ])
+            .spacing(2.0)
+            .width(Length::FillPortion(1))
+            .into(),
+        ])
+        .align_items(Alignment::Center)
         .into()
     }
 
-    fn food_tab(&self) -> Element<Message> {
-        Column::with_children(vec![
-            TextInput::new("Find Food...", &self.search_text)
-                .on_input(Message::ChangeSearchText)
-                .into(),
-            FloatingElement::new(
-                Scrollable::new(Column::with_children(
-                    self.foods
+    /// Distinct foods of the most recent feed entries, newest first.
+    fn recent_food_ids(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = Vec::new();
+        for entry in self.feed.iter().rev() {
+            if ids.len() == FOOD_SHORTLIST_LEN {
+                break;
+            }
+            if let Some(food_id) = entry.food_id {
+                if !ids.contains(&food_id) {
+                    ids.push(food_id);
+                }
+            }
+        }
+        ids
+    }
+
+    /// Foods logged most often, most frequent first.
+    fn frequent_food_ids(&self) -> Vec<u32> {
+        let mut counts: HashMap<u32, u32> = HashMap::new();
+        for food_id in self.feed.iter().filter_map(|entry| entry.food_id) {
+            *counts.entry(food_id).or_default() += 1;
+        }
+        let mut ids: Vec<(u32, u32)> = counts.into_iter().collect();
+        ids.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ids.into_iter()
+            .take(FOOD_SHORTLIST_LEN)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Finds every pair of foods whose names closely match, offered by the merge tool.
+    fn refresh_duplicate_foods(&mut self) {
+        self.duplicate_foods = Vec::new();
+        for (index, a) in self.foods.iter().enumerate() {
+            for b in self.foods[index + 1..].iter() {
+                if search::name_similarity(&a.name, &b.name) >= search::DUPLICATE_THRESHOLD {
+                    self.duplicate_foods.push((a.id, b.id));
+                }
+            }
+        }
+    }
+
+    /// Finds the foods other than the one being edited whose names closely match the name
+    /// entered in the add/edit form.
+    fn refresh_similar_foods(&mut self) {
+        self.similar_food_ids = self
+            .foods
+            .iter()
+            .filter(|food| food.id != self.next_food_id)
+            .filter(|food| {
+                search::name_similarity(&self.add_food_name, &food.name)
+                    >= search::DUPLICATE_THRESHOLD
+            })
+            .map(|food| food.id)
+            .collect();
+    }
+
+    /// Confirmation of a pending deletion, `None` once its target is gone.
+    fn confirm_delete(&self, pending_delete: PendingDelete) -> Option<Element<'_, Message>> {
+        let cancel = Button::new(self.tr("Cancel"))
+            .on_press(Message::ConfirmDelete(ConfirmDelete::Cancel))
+            .style(theme::Button::Secondary);
+
+        let (title, details, buttons): (String, String, Vec<Element<Message>>) =
+            match pending_delete {
+                PendingDelete::Food(food_id) => {
+                    let food = self.food(food_id)?;
+                    let affected = self
+                        .feed
                         .iter()
-                        .enumerate()
-                        .map(|(index, food)| {
-                            Container::new(
-                                Row::with_children(vec![
-                                    Row::with_children(vec![
-                                        Text::new(&food.name).width(300).into(),
-                                        Space::new(20, 10).into(),
-                                        Text::new("-").into(),
-                                        Space::new(20, 10).into(),
-                                        Text::new(format!(
-                                            "Serving Size: {} Carbs: {} Fats: {:.1} Proteins: {:.1} Calories: {:.1}",
-                                            food.serving_size,
-                                            food.carbs,
-                                            food.fats,
-                                            food.protein,
-                                            food.calories()
-                                        ))
-                                        .into(),
-                                    ])
-                                    .width(Length::FillPortion(5))
-                                    .align_items(Alignment::Center)
-                                    .into(),
-                                    Row::with_children(vec![
-                                        TextInput::new("Serving Size", &food.current_serving_size)
-                                            .on_input(|new_str| {
-                                                Message::FoodServingCurrentServingSizeChanged(
-                                                    food.id, new_str,
-                                                )
-                                            })
-                                            .into(),
-                                        Button::new(Text::new("＋").shaping(Shaping::Advanced))
-                                            .on_press(Message::AddFeedEntry(food.clone()))
-                                            .style(theme::Button::Positive)
-                                            .into(),
-                                        Button::new(Text::new("⚙").shaping(Shaping::Advanced))
-                                            .on_press(Message::ModifyFood(food.clone()))
-                                            .into(),
-                                        Button::new(Text::new("🗑").shaping(Shaping::Advanced))
-                                            .on_press(Message::DeleteFood(food.clone()))
-                                            .style(theme::Button::Destructive)
-                                            .into(),
-                                    ])
-                                    .spacing(2.0)
-                                    .width(Length::FillPortion(1))
-                                    .into(),
-                                ])
-                                .align_items(Alignment::Center),
+                        .filter(|entry| entry.food_id == Some(food_id))
+                        .count();
+                    let mut buttons: Vec<Element<Message>> = vec![cancel.into()];
+                    if !food.archived {
+                        buttons.push(
+                            Button::new(self.tr("Archive instead"))
+                                .on_press(Message::ConfirmDelete(ConfirmDelete::Archive))
+                                .style(theme::Button::Primary)
+                                .into(),
+                        );
+                    }
+                    buttons.push(
+                        Button::new(self.tr(if affected == 0 { "Delete" } else { "Delete anyway" }))
+                            .on_press(Message::ConfirmDelete(ConfirmDelete::Delete))
+                            .style(theme::Button::Destructive)
+                            .into(),
+                    );
+                    (
+                        self.tr_args("Delete {0}?", &[&food.name]),
+                        if affected == 0 {
+                            self.tr("No diary entries use this food.").to_string()
+                        } else {
+                            self.tr_args(
+                                "{0} diary entries use this food and will be deleted with it. Archiving hides the food but keeps them.",
+                                &[&affected],
                             )
-                            .width(Length::Fill)
-                            .padding(10)
-                            .style(move |_theme: &Theme| {
-                                if index % 2 == 0 {
-                                    Appearance {
-                                        text_color: None,
-                                        background: Some(Background::Color(Color::new(
-                                            0.9, 0.9, 0.9, 1.0,
-                                        ))),
-                                        border_radius: Default::default(),
-                                        border_width: 0.0,
-                                        border_color: Default::default(),
-                                    }
-                                } else {
-                                    Appearance {
-                                        text_color: None,
-                                        background: Some(Background::Color(Color::new(
-                                            0.95, 0.95, 0.95, 1.0,
-                                        ))),
-                                        border_radius: Default::default(),
-                                        border_width: 0.0,
-                                        border_color: Default::default(),
-                                    }
-                                }
-                            })
-                            .into()
+                        },
+                        buttons,
+                    )
+                }
+                PendingDelete::SelectedFoods => {
+                    let foods: Vec<&Food> = self
+                        .selected_foods
+                        .iter()
+                        .filter_map(|food_id| self.food(*food_id))
+                        .collect();
+                    if foods.is_empty() {
+                        return None;
+                    }
+                    let affected = self
+                        .feed
+                        .iter()
+                        .filter(|entry| {
+                            entry
+                                .food_id
+                                .is_some_and(|food_id| self.selected_foods.contains(&food_id))
                         })
-                        .collect(),
-                ))
-                .width(Length::Fill)
-                .height(Length::Fill),
-                Button::new(Text::new("Create Food").shaping(Shaping::Advanced)).on_press(Message::AddFood(AddFood::BeginAdd)),
+                        .count();
+                    let mut buttons: Vec<Element<Message>> = vec![cancel.into()];
+                    if foods.iter().any(|food| !food.archived) {
+                        buttons.push(
+                            Button::new(self.tr("Archive instead"))
+                                .on_press(Message::ConfirmDelete(ConfirmDelete::Archive))
+                                .style(theme::Button::Primary)
+                                .into(),
+                        );
+                    }
+                    buttons.push(
+                        Button::new(self.tr(if affected == 0 { "Delete" } else { "Delete anyway" }))
+                            .on_press(Message::ConfirmDelete(ConfirmDelete::Delete))
+                            .style(theme::Button::Destructive)
+                            .into(),
+                    );
+                    (
+                        self.tr_args("Delete {0} foods?", &[&foods.len()]),
+                        if affected == 0 {
+                            self.tr("No diary entries use these foods.").to_string()
+                        } else {
+                            self.tr_args(
+                                "{0} diary entries use these foods and will be deleted with them. Archiving hides the foods but keeps them.",
+                                &[&affected],
+                            )
+                        },
+                        buttons,
+                    )
+                }
+                PendingDelete::FeedEntry(feed_entry_id) => {
+                    let entry = self.feed.iter().find(|entry| entry.id == feed_entry_id)?;
+                    let name = match (&entry.quick_add, entry.food_id.and_then(|id| self.food(id))) {
+                        (Some(quick_add), _) if !quick_add.label.is_empty() => quick_add.label.clone(),
+                        (Some(_), _) => self.tr("Quick Add").to_string(),
+                        (None, Some(food)) => food.name.clone(),
+                        (None, None) => self.tr("Unknown food").to_string(),
+                    };
+                    (
+                        self.tr("Delete this entry?").to_string(),
+                        self.tr_args(
+                            "{0} {1}, {2} servings",
+                            &[
+                                &self.i18n.date_time(&entry.local_time()),
+                                &name,
+                                &self.i18n.input(entry.amount),
+                            ],
+                        ),
+                        vec![
+                            cancel.into(),
+                            Button::new(self.tr("Delete"))
+                                .on_press(Message::ConfirmDelete(ConfirmDelete::Delete))
+                                .style(theme::Button::Destructive)
+                                .into(),
+                        ],
+                    )
+                }
+            };
+
+        Some(
+            Container::new(
+                Column::with_children(vec![
+                    Text::new(title).size(20).into(),
+                    Text::new(details).into(),
+                    Row::with_children(buttons).spacing(30).into(),
+                ])
+                .spacing(20)
+                .width(500),
             )
-            .anchor(Anchor::SouthEast)
-            .offset(Offset::from(20.0))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Horizontal::Center)
+            .align_y(Vertical::Center)
+            .padding(20)
             .into(),
-        ])
-        .into()
+        )
     }
 
-    fn add_food(&self) -> Element<Message> {
-        const LABEL_WIDTH: u16 = 100;
+    fn merge_foods(&self) -> Element<'_, Message> {
+        const LABEL_WIDTH: u16 = 150;
+        let choice = |id: Option<u32>| {
+            id.and_then(|id| self.food(id)).map(|food| FoodChoice {
+                id: food.id,
+                name: food.name.clone(),
+            })
+        };
+        let affected = self
+            .merge_duplicate
+            .map(|id| {
+                self.feed
+                    .iter()
+                    .filter(|entry| entry.food_id == Some(id))
+                    .count()
+            })
+            .unwrap_or(0);
+
+        let suggestions: Vec<Element<Message>> = self
+            .duplicate_foods
+            .iter()
+            .filter_map(|&(a, b)| Some((self.food(a)?, self.food(b)?)))
+            .map(|(a, b)| {
+                Button::new(Text::new(format!("{} / {}", a.name, b.name)))
+                    .on_press(Message::MergeSuggestionSelected(a.id, b.id))
+                    .style(theme::Button::Secondary)
+                    .into()
+            })
+            .collect();
+
+        let food_details = |id: Option<u32>| -> Element<Message> {
+            Text::new(match id.and_then(|id| self.food(id)) {
+                Some(food) => self.food_summary(food),
+                None => String::from(""),
+            })
+            .into()
+        };
+
         Container::new(
             Column::with_children(vec![
+                Text::new(self.tr("Merge Foods")).size(20).into(),
                 Row::with_children(vec![
-                    Text::new("Name:").width(LABEL_WIDTH).into(),
-                    TextInput::new("Enter Name", &self.add_food_name)
-                        .on_input(Message::AddFoodNameChanged)
-                        .into(),
+                    Text::new(self.tr("Duplicate to remove:")).width(LABEL_WIDTH).into(),
+                    PickList::new(
+                        self.food_choices(),
+                        choice(self.merge_duplicate),
+                        Message::MergeDuplicateSelected,
+                    )
+                    .placeholder(self.tr("Select Food"))
+                    .width(300)
+                    .into(),
                 ])
                 .align_items(Alignment::Center)
                 .spacing(15)
-                .width(300)
                 .into(),
+                food_details(self.merge_duplicate),
                 Row::with_children(vec![
-                    Text::new("Serving Size:").width(LABEL_WIDTH).into(),
-                    TextInput::new("Enter Serving Size", &self.add_food_serving_size)
-                        .on_input(Message::AddFoodServingSizeChanged)
-                        .into(),
+                    Text::new(self.tr("Food to keep:")).width(LABEL_WIDTH).into(),
+                    PickList::new(
+                        self.food_choices(),
+                        choice(self.merge_survivor),
+                        Message::MergeSurvivorSelected,
+                    )
+                    .placeholder(self.tr("Select Food"))
+                    .width(300)
+                    .into(),
                 ])
-                .align_items(Alignment::End)
+                .align_items(Alignment::Center)
                 .spacing(15)
-                .width(300)
+                .into(),
+                food_details(self.merge_survivor),
+                Text::new(self.tr_args(
+                    "{0} feed entries will be moved to the food to keep.",
+                    &[&affected],
+                ))
                 .into(),
                 Row::with_children(vec![
-                    Text::new("Carbs:").width(LABEL_WIDTH).into(),
-                    TextInput::new("Enter Carbs", &self.add_food_carbs)
-                        .on_input(Message::AddFoodCarbsChanged)
+                    Button::new(self.tr("Cancel"))
+                        .on_press(Message::MergeFood(MergeFood::Cancel))
+                        .style(theme::Button::Destructive)
+                        .into(),
+                    Button::new(self.tr("Merge"))
+                        .on_press(Message::MergeFood(MergeFood::FinishMerge))
+                        .style(theme::Button::Positive)
                         .into(),
                 ])
-                .align_items(Alignment::End)
-                .spacing(15)
+                .spacing(30)
+                .into(),
+                Text::new(self.tr(if suggestions.is_empty() {
+                    "No likely duplicates found."
+                } else {
+                    "Likely duplicates:"
+                }))
+                .into(),
+                Scrollable::new(Column::with_children(suggestions).spacing(5))
+                    .height(Length::Fill)
+                    .into(),
+            ])
+            .spacing(20),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(20)
+        .into()
+    }
+
+    /// When editing, the choice of whether changes apply to past entries and the list of
+    /// earlier revisions that can be restored into the form.
+    fn food_revision_controls(&self) -> Element<'_, Message> {
+        let Some(food) = self.food(self.next_food_id) else {
+            return Space::new(0, 0).into();
+        };
+
+        let mut controls: Vec<Element<Message>> = vec![
+            Radio::new(
+                self.tr("Apply changes only from now on"),
+                false,
+                Some(self.add_food_apply_to_past),
+                Message::AddFoodApplyToPastChanged,
+            )
+            .into(),
+            Radio::new(
+                self.tr("Apply changes to past entries too"),
+                true,
+                Some(self.add_food_apply_to_past),
+                Message::AddFoodApplyToPastChanged,
+            )
+            .into(),
+        ];
+        if !food.revisions.is_empty() {
+            controls.push(Text::new(self.tr("Earlier revisions:")).into());
+        }
+        for (index, revision) in food.revisions.iter().enumerate().rev() {
+            controls.push(
+                Row::with_children(vec![
+                    Text::new(self.tr_args(
+                        "{0} - {1}, C {2} F {3} P {4}{5}",
+                        &[
+                            &self.i18n.date_time(&DateTime::<Local>::from(revision.replaced)),
+                            &revision.serving_size,
+                            &self.number(revision.carbs),
+                            &self.number(revision.fats),
+                            &self.number(revision.protein),
+                            &if revision.valid_until.is_some() {
+                                self.tr(" (kept by older entries)")
+                            } else {
+                                ""
+                            },
+                        ],
+                    ))
+                    .size(14)
+                    .width(Length::Fill)
+                    .into(),
+                    Button::new(Text::new(self.tr("Restore")).size(14))
+                        .on_press(Message::RestoreFoodRevision(index))
+                        .style(theme::Button::Secondary)
+                        .into(),
+                ])
+                .align_items(Alignment::Center)
+                .spacing(5)
+                .into(),
+            );
+        }
+
+        Column::with_children(controls).spacing(5).width(300).into()
+    }
+
+    fn add_food(&self) -> Element<'_, Message> {
+        const LABEL_WIDTH: u16 = 100;
+        let errors = self.food_form_errors();
+        let energy_label = self.tr_args("Energy ({0}):", &[&self.settings.energy_unit]);
+        let missing = errors.missing();
+        let mut add_button = Button::new(self.tr("Add")).style(theme::Button::Positive);
+        if errors.is_valid() {
+            add_button = add_button.on_press(Message::AddFood(AddFood::FinishAdd));
+        }
+        Container::new(
+            Column::with_children(vec![
+                form_field(
+                    self.tr("Name:"),
+                    LABEL_WIDTH,
+                    TextInput::new(self.tr("Enter Name"), &self.add_food_name)
+                        .on_input(Message::AddFoodNameChanged),
+                    &errors.name,
+                    &self.theme,
+                ),
+                Text::new(if self.similar_food_ids.is_empty() {
+                    String::from("")
+                } else {
+                    self.tr_args(
+                        "Similar to existing: {0}",
+                        &[&self
+                            .similar_food_ids
+                            .iter()
+                            .filter_map(|&id| self.food(id))
+                            .map(|food| food.name.as_str())
+                            .collect::<Vec<&str>>()
+                            .join(", ")],
+                    )
+                })
+                .style(themes::warning(&self.theme))
                 .width(300)
                 .into(),
                 Row::with_children(vec![
-                    Text::new("Fats:").width(LABEL_WIDTH).into(),
-                    TextInput::new("Enter Fats", &self.add_food_fats)
-                        .on_input(Message::AddFoodFatsChanged)
+                    Text::new(self.tr("Brand:")).width(LABEL_WIDTH).into(),
+                    TextInput::new(self.tr("Optional"), &self.add_food_brand)
+                        .on_input(Message::AddFoodBrandChanged)
                         .into(),
                 ])
                 .align_items(Alignment::End)
@@ -690,28 +3934,166 @@ impl Macros {
                 .width(300)
                 .into(),
                 Row::with_children(vec![
-                    Text::new("Proteins:").width(LABEL_WIDTH).into(),
-                    TextInput::new("Enter Proteins", &self.add_food_proteins)
-                        .on_input(Message::AddFoodProteinsChanged)
+                    Text::new(self.tr("Aliases:")).width(LABEL_WIDTH).into(),
+                    TextInput::new(self.tr("Comma separated"), &self.add_food_aliases)
+                        .on_input(Message::AddFoodAliasesChanged)
                         .into(),
                 ])
                 .align_items(Alignment::End)
                 .spacing(15)
                 .width(300)
                 .into(),
-                Column::with_children(vec![Row::with_children(vec![
-                    Button::new("Cancel")
-                        .on_press(Message::AddFood(AddFood::Cancel))
-                        .style(theme::Button::Destructive)
+                Row::with_children(vec![
+                    Text::new(self.tr("Tags:")).width(LABEL_WIDTH).into(),
+                    TextInput::new(self.tr("Add Tag"), &self.add_food_tag_input)
+                        .on_input(Message::AddFoodTagInputChanged)
+                        .on_submit(Message::AddFoodTagAdded)
                         .into(),
-                    Button::new("Add")
-                        .on_press(Message::AddFood(AddFood::FinishAdd))
-                        .style(theme::Button::Positive)
+                    Button::new(Text::new("＋").shaping(Shaping::Advanced))
+                        .on_press(Message::AddFoodTagAdded)
                         .into(),
                 ])
                 .align_items(Alignment::Center)
-                .spacing(30)
-                .into()])
+                .spacing(15)
+                .width(300)
+                .into(),
+                Row::with_children(
+                    self.add_food_tags
+                        .iter()
+                        .map(|tag| {
+                            Button::new(Text::new(format!("{} ✕", tag)).size(14).shaping(Shaping::Advanced))
+                                .on_press(Message::AddFoodTagRemoved(tag.clone()))
+                                .style(theme::Button::Secondary)
+                                .into()
+                        })
+                        .collect(),
+                )
+                .spacing(5)
+                .width(300)
+                .into(),
+                form_field(
+                    self.tr("Serving Size:"),
+                    LABEL_WIDTH,
+                    TextInput::new(self.tr("Enter Serving Size"), &self.add_food_serving_size)
+                        .on_input(Message::AddFoodServingSizeChanged),
+                    &errors.serving_size,
+                    &self.theme,
+                ),
+                Row::with_children(vec![
+                    Text::new(self.tr("Serving Weight:")).width(LABEL_WIDTH).into(),
+                    TextInput::new(
+                        self.tr(if self.add_food_per_100 {
+                            "Required"
+                        } else {
+                            "Optional"
+                        }),
+                        &self.add_food_serving_weight,
+                    )
+                    .on_input(Message::AddFoodServingWeightChanged)
+                    .style(match errors.serving_weight {
+                        Some(FieldError::Invalid(_)) => themes::invalid_input(),
+                        _ => theme::TextInput::Default,
+                    })
+                    .into(),
+                    PickList::new(
+                        &ServingUnit::ALL[..],
+                        Some(self.add_food_serving_unit),
+                        Message::AddFoodServingUnitChanged,
+                    )
+                    .width(60)
+                    .into(),
+                ])
+                .align_items(Alignment::Center)
+                .spacing(15)
+                .width(300)
+                .into(),
+                field_error(&errors.serving_weight, &self.theme),
+                Checkbox::new(
+                    self.tr_args(
+                        "Values below are per 100 {0}",
+                        &[&self.add_food_serving_unit.suffix()],
+                    ),
+                    self.add_food_per_100,
+                    Message::AddFoodPer100Toggled,
+                )
+                .width(300)
+                .into(),
+                form_field(
+                    self.tr("Carbs:"),
+                    LABEL_WIDTH,
+                    TextInput::new(self.tr("Enter Carbs"), &self.add_food_carbs)
+                        .on_input(Message::AddFoodCarbsChanged),
+                    &errors.carbs,
+                    &self.theme,
+                ),
+                form_field(
+                    self.tr("Fats:"),
+                    LABEL_WIDTH,
+                    TextInput::new(self.tr("Enter Fats"), &self.add_food_fats)
+                        .on_input(Message::AddFoodFatsChanged),
+                    &errors.fats,
+                    &self.theme,
+                ),
+                form_field(
+                    self.tr("Proteins:"),
+                    LABEL_WIDTH,
+                    TextInput::new(self.tr("Enter Proteins"), &self.add_food_proteins)
+                        .on_input(Message::AddFoodProteinsChanged),
+                    &errors.protein,
+                    &self.theme,
+                ),
+                form_field(
+                    self.tr("Alcohol:"),
+                    LABEL_WIDTH,
+                    TextInput::new(self.tr("Optional"), &self.add_food_alcohol)
+                        .on_input(Message::AddFoodAlcoholChanged),
+                    &errors.alcohol,
+                    &self.theme,
+                ),
+                form_field(
+                    &energy_label,
+                    LABEL_WIDTH,
+                    TextInput::new(self.tr("From label, optional"), &self.add_food_energy)
+                        .on_input(Message::AddFoodEnergyChanged),
+                    &errors.energy,
+                    &self.theme,
+                ),
+                form_field(
+                    self.tr("Water (ml):"),
+                    LABEL_WIDTH,
+                    TextInput::new(self.tr("Optional"), &self.add_food_water)
+                        .on_input(Message::AddFoodWaterChanged),
+                    &errors.water,
+                    &self.theme,
+                ),
+                self.food_revision_controls(),
+                Column::with_children(vec![
+                    Row::with_children(vec![
+                        Button::new(self.tr("Cancel"))
+                            .on_press(Message::AddFood(AddFood::Cancel))
+                            .style(theme::Button::Destructive)
+                            .into(),
+                        add_button.into(),
+                    ])
+                    .align_items(Alignment::Center)
+                    .spacing(30)
+                    .into(),
+                    Text::new(if missing.is_empty() {
+                        String::new()
+                    } else {
+                        self.tr_args(
+                            "Required: {0}",
+                            &[&missing
+                                .iter()
+                                .map(|field| self.tr(field))
+                                .collect::<Vec<&str>>()
+                                .join(", ")],
+                        )
+                    })
+                    .style(themes::muted(&self.theme))
+                    .into(),
+                ])
+                .spacing(10)
                 .width(300)
                 .align_items(Alignment::Center)
                 .into(),
@@ -732,36 +4114,475 @@ pub struct Food {
     id: u32,
     name: String,
     brand: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    /// User defined categories such as "dairy" or "snack", always lowercase.
+    #[serde(default)]
+    tags: Vec<String>,
     carbs: f32,
     fats: f32,
     protein: f32,
+    #[serde(default)]
+    alcohol: f32,
+    /// Energy in kcal per serving stated on the label, used instead of the energy factors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    energy: Option<f32>,
     serving_size: String,
+    /// Weight in g (or volume in ml) of one serving, allows logging by weight.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    serving_weight: Option<f32>,
+    #[serde(default)]
+    serving_unit: ServingUnit,
+    /// Label values, known whenever the serving weight is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    per_100: Option<Per100>,
+    /// Whether the values were entered per 100 g/ml rather than per serving, the add/edit
+    /// form reopens the same way.
+    #[serde(default)]
+    entered_per_100: bool,
+    /// Volume in ml per serving that counts toward daily hydration.
+    #[serde(default)]
+    water: f32,
+    #[serde(default)]
+    favorite: bool,
+    /// Archived foods are hidden from the food tab but still resolve for feed entries.
+    #[serde(default)]
+    archived: bool,
+    /// Values replaced by earlier edits, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    revisions: Vec<FoodRevision>,
 
     #[serde(skip_serializing, skip_deserializing)]
     current_serving_size: String,
     #[serde(skip_serializing, skip_deserializing)]
     relevance: f32,
+    /// Character ranges of the name matched by the current search.
+    #[serde(skip_serializing, skip_deserializing)]
+    highlight: Vec<Range<usize>>,
+}
+
+/// Nutrition values a food had before an edit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoodRevision {
+    /// When these values were replaced.
+    replaced: DateTime<Utc>,
+    /// Entries logged before this time keep these values, `None` once an edit was applied
+    /// to past entries.
+    valid_until: Option<DateTime<Utc>>,
+    serving_size: String,
+    carbs: f32,
+    fats: f32,
+    protein: f32,
+    #[serde(default)]
+    alcohol: f32,
+    #[serde(default)]
+    energy: Option<f32>,
+    water: f32,
+    serving_weight: Option<f32>,
+    serving_unit: ServingUnit,
+    per_100: Option<Per100>,
+    #[serde(default)]
+    entered_per_100: bool,
+}
+
+impl FoodRevision {
+    /// Energy of one serving in kcal.
+    fn energy(&self, factors: &EnergyFactors) -> f32 {
+        self.energy.unwrap_or_else(|| {
+            factors.calories(self.carbs, self.fats, self.protein, self.alcohol)
+        })
+    }
+
+    fn same_values(&self, other: &FoodRevision) -> bool {
+        self.serving_size == other.serving_size
+            && self.carbs == other.carbs
+            && self.fats == other.fats
+            && self.protein == other.protein
+            && self.alcohol == other.alcohol
+            && self.energy == other.energy
+            && self.water == other.water
+            && self.serving_weight == other.serving_weight
+            && self.serving_unit == other.serving_unit
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct FeedEntry {
     id: u32,
-    food_id: u32,
+    /// `None` for entries logged with quick add.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    food_id: Option<u32>,
     amount: f32,
     date: DateTime<Utc>,
+    /// Seconds east of UTC where the entry was logged, so days keep their entries after
+    /// travelling. Older entries get the local offset at their date when loaded.
+    #[serde(default)]
+    utc_offset: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    quick_add: Option<QuickAdd>,
+}
 
-    #[serde(skip_serializing, skip_deserializing)]
-    is_daily_total: bool,
-    #[serde(skip_serializing, skip_deserializing)]
+impl Recorded for FeedEntry {
+    fn date(&self) -> DateTime<Utc> {
+        self.date
+    }
+
+    fn utc_offset(&self) -> Option<i32> {
+        self.utc_offset
+    }
+
+    fn utc_offset_mut(&mut self) -> &mut Option<i32> {
+        &mut self.utc_offset
+    }
+}
+
+/// Macros entered directly on a feed entry, without a food in the library.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QuickAdd {
+    label: String,
+    carbs: f32,
+    fats: f32,
+    protein: f32,
+    #[serde(default)]
+    alcohol: f32,
+}
+
+impl QuickAdd {
+    fn energy(&self, factors: &EnergyFactors) -> f32 {
+        factors.calories(self.carbs, self.fats, self.protein, self.alcohol)
+    }
+}
+
+/// Problem with a single field of the add/edit food form.
+#[derive(Debug, Clone, PartialEq)]
+enum FieldError {
+    /// A required field is empty, listed next to the add button rather than inline.
+    Missing,
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Default)]
+struct FoodFormErrors {
+    name: Option<FieldError>,
+    serving_size: Option<FieldError>,
+    serving_weight: Option<FieldError>,
+    carbs: Option<FieldError>,
+    fats: Option<FieldError>,
+    protein: Option<FieldError>,
+    alcohol: Option<FieldError>,
+    energy: Option<FieldError>,
+    water: Option<FieldError>,
+}
+
+impl FoodFormErrors {
+    fn fields(&self) -> [(&'static str, &Option<FieldError>); 9] {
+        [
+            ("Name", &self.name),
+            ("Serving Size", &self.serving_size),
+            ("Serving Weight", &self.serving_weight),
+            ("Carbs", &self.carbs),
+            ("Fats", &self.fats),
+            ("Proteins", &self.protein),
+            ("Alcohol", &self.alcohol),
+            ("Energy", &self.energy),
+            ("Water", &self.water),
+        ]
+    }
+
+    fn is_valid(&self) -> bool {
+        self.fields().iter().all(|(_, error)| error.is_none())
+    }
+
+    /// Names of the required fields that are still empty.
+    fn missing(&self) -> Vec<&'static str> {
+        self.fields()
+            .into_iter()
+            .filter(|(_, error)| **error == Some(FieldError::Missing))
+            .map(|(name, _)| name)
+            .collect()
+    }
+}
+
+/// Entry of the food pick lists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoodChoice {
+    id: u32,
+    name: String,
+}
+
+impl std::fmt::Display for FoodChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Foods and feed before an undoable change.
+struct UndoSnapshot {
+    foods: Vec<Food>,
+    feed: Vec<FeedEntry>,
+    templates: Vec<MealTemplate>,
+    data_version: u64,
+}
+
+/// A calendar day of the feed along with its cached macro totals.
+struct Day {
+    date: NaiveDate,
+    /// Indices into `Macros::feed`, oldest first.
+    entries: Vec<usize>,
+    /// Indices into `Macros::water_log.entries`, oldest first.
+    water_entries: Vec<usize>,
     carbs: f32,
-    #[serde(skip_serializing, skip_deserializing)]
     fats: f32,
-    #[serde(skip_serializing, skip_deserializing)]
     protein: f32,
+    alcohol: f32,
+    /// Energy in kcal, label values where given and the energy factors otherwise.
+    calories: f32,
+    /// Water in ml from both the water log and beverages.
+    water: f32,
 }
 
 impl Food {
-    fn calories(&self) -> f32 {
-        self.carbs * 4.0 + self.fats * 9.0 + self.protein * 4.0
+    /// Energy of one serving in kcal.
+    fn energy(&self, factors: &EnergyFactors) -> f32 {
+        self.energy.unwrap_or_else(|| {
+            factors.calories(self.carbs, self.fats, self.protein, self.alcohol)
+        })
+    }
+
+    fn current_revision(&self) -> FoodRevision {
+        FoodRevision {
+            replaced: Utc::now(),
+            valid_until: None,
+            serving_size: self.serving_size.clone(),
+            carbs: self.carbs,
+            fats: self.fats,
+            protein: self.protein,
+            alcohol: self.alcohol,
+            energy: self.energy,
+            water: self.water,
+            serving_weight: self.serving_weight,
+            serving_unit: self.serving_unit,
+            per_100: self.per_100,
+            entered_per_100: self.entered_per_100,
+        }
+    }
+
+    /// Earlier values in effect for an entry logged at `date`, `None` if the current values
+    /// apply.
+    fn revision_at(&self, date: DateTime<Utc>) -> Option<&FoodRevision> {
+        self.revisions
+            .iter()
+            .find(|revision| revision.valid_until.is_some_and(|until| date < until))
+    }
+
+    /// Serving size along with its weight when known, e.g. "1 cup (240 ml)".
+    fn serving_description(&self, i18n: &Catalog) -> String {
+        match self.serving_weight {
+            Some(weight) => format!(
+                "{} ({} {})",
+                self.serving_size,
+                i18n.input(weight),
+                self.serving_unit.suffix()
+            ),
+            None => self.serving_size.clone(),
+        }
+    }
+}
+
+impl Day {
+    fn new(date: NaiveDate) -> Self {
+        Day {
+            date,
+            entries: Vec::new(),
+            water_entries: Vec::new(),
+            carbs: 0.0,
+            fats: 0.0,
+            protein: 0.0,
+            alcohol: 0.0,
+            calories: 0.0,
+            water: 0.0,
+        }
+    }
+}
+
+/// Food name followed by its brand, with the parts matched by the search emphasized.
+fn highlighted_name<'a>(food: &'a Food, theme: &Theme, i18n: &'a Catalog) -> Element<'a, Message> {
+    let chars: Vec<char> = food.name.chars().collect();
+    let mut segments: Vec<Element<Message>> = Vec::new();
+    let mut position = 0;
+    for range in food.highlight.iter() {
+        if range.start > position {
+            segments.push(Text::new(chars[position..range.start].iter().collect::<String>()).into());
+        }
+        segments.push(
+            Text::new(chars[range.start..range.end].iter().collect::<String>())
+                .style(theme.palette().primary)
+                .into(),
+        );
+        position = range.end;
+    }
+    if position < chars.len() {
+        segments.push(Text::new(chars[position..].iter().collect::<String>()).into());
+    }
+    if !food.brand.is_empty() {
+        segments.push(
+            Text::new(format!(" ({})", food.brand))
+                .style(themes::muted(theme))
+                .into(),
+        );
+    }
+    if food.archived {
+        segments.push(
+            Text::new(i18n.tr(" [archived]"))
+                .style(themes::muted(theme))
+                .into(),
+        );
+    }
+
+    Row::with_children(segments).width(300).into()
+}
+
+fn setting_row<'a>(
+    label: &'a str,
+    placeholder: &'a str,
+    value: &'a str,
+    on_input: fn(String) -> Message,
+) -> Element<'a, Message> {
+    labelled_setting(label, TextInput::new(placeholder, value).on_input(on_input))
+}
+
+fn labelled_setting<'a>(label: &'a str, input: TextInput<'a, Message>) -> Element<'a, Message> {
+    Row::with_children(vec![
+        Text::new(label).width(150).into(),
+        input.width(300).into(),
+    ])
+    .spacing(10)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+/// Labelled input of the add/edit food form, marked and explained when its value is invalid.
+fn form_field<'a>(
+    label: &str,
+    label_width: u16,
+    input: TextInput<'a, Message>,
+    error: &Option<FieldError>,
+    theme: &Theme,
+) -> Element<'a, Message> {
+    let input = match error {
+        Some(FieldError::Invalid(_)) => input.style(themes::invalid_input()),
+        _ => input,
+    };
+    Column::with_children(vec![
+        Row::with_children(vec![
+            Text::new(label.to_string()).width(label_width).into(),
+            input.into(),
+        ])
+        .align_items(Alignment::Center)
+        .spacing(15)
+        .width(300)
+        .into(),
+        field_error(error, theme),
+    ])
+    .spacing(5)
+    .into()
+}
+
+/// Message below an invalid form field, empty when the field is valid or just missing.
+fn field_error<'a>(error: &Option<FieldError>, theme: &Theme) -> Element<'a, Message> {
+    match error {
+        Some(FieldError::Invalid(message)) => Text::new(message.clone())
+            .size(14)
+            .style(theme.palette().danger)
+            .into(),
+        _ => Space::new(0, 0).into(),
+    }
+}
+
+/// Contents of `file` in the data directory, `empty` stands in for a missing file.
+fn load_data<T: DeserializeOwned>(
+    config_path: &Path,
+    file: &str,
+    empty: &str,
+    i18n: &Catalog,
+) -> Result<T, String> {
+    let path = config_path.join(file);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => String::from(empty),
+        Err(error) => {
+            return Err(i18n.tr_args("Failed to read {0}: {1}", &[&path.display(), &error]))
+        }
+    };
+    serde_json::from_str(&contents)
+        .map_err(|error| i18n.tr_args("Failed to parse {0}: {1}", &[&path.display(), &error]))
+}
+
+/// Path typed into a settings input, unset when blank.
+fn path_setting(input: &str) -> Option<PathBuf> {
+    let input = input.trim();
+    if input.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(input))
     }
 }
+
+fn search_input_id() -> text_input::Id {
+    text_input::Id::new("search")
+}
+
+fn palette_input_id() -> text_input::Id {
+    text_input::Id::new("palette")
+}
+
+fn quick_add_input_id() -> text_input::Id {
+    text_input::Id::new("quick_add")
+}
+
+fn template_name_input_id() -> text_input::Id {
+    text_input::Id::new("template_name")
+}
+
+fn weight_input_id() -> text_input::Id {
+    text_input::Id::new("weight")
+}
+
+fn bulk_tag_input_id() -> text_input::Id {
+    text_input::Id::new("bulk_tag")
+}
+
+fn bulk_brand_input_id() -> text_input::Id {
+    text_input::Id::new("bulk_brand")
+}
+
+fn section_header(title: &str) -> Element<'_, Message> {
+    Container::new(Text::new(title).size(20))
+        .padding([15, 10, 5, 10])
+        .into()
+}
+
+fn striped_row(index: usize, content: Element<'_, Message>) -> Element<'_, Message> {
+    striped_container(index, content).into()
+}
+
+/// Striped row of the feed, where all rows share one height.
+fn feed_row(index: usize, height: f32, content: Element<'_, Message>) -> Element<'_, Message> {
+    striped_container(index, content)
+        .height(height)
+        .center_y()
+        .into()
+}
+
+fn striped_container(index: usize, content: Element<'_, Message>) -> Container<'_, Message> {
+    Container::new(content)
+        .width(Length::Fill)
+        .padding(ROW_PADDING)
+        .style(move |theme: &Theme| Appearance {
+            text_color: None,
+            background: Some(Background::Color(themes::stripe(theme, index))),
+            border_radius: Default::default(),
+            border_width: 0.0,
+            border_color: Default::default(),
+        })
+}
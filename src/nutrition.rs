@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Unit of a food's serving weight, liquids are labelled per 100 ml instead of per 100 g.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ServingUnit {
+    #[default]
+    Grams,
+    Milliliters,
+}
+
+impl ServingUnit {
+    pub const ALL: [ServingUnit; 2] = [ServingUnit::Grams, ServingUnit::Milliliters];
+
+    pub fn suffix(self) -> &'static str {
+        match self {
+            ServingUnit::Grams => "g",
+            ServingUnit::Milliliters => "ml",
+        }
+    }
+}
+
+impl fmt::Display for ServingUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
+
+/// Nutrients per 100 g or 100 ml as printed on nutrition labels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Per100 {
+    pub carbs: f32,
+    pub fats: f32,
+    pub protein: f32,
+    #[serde(default)]
+    pub alcohol: f32,
+    /// Energy in kcal stated on the label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub energy: Option<f32>,
+}
+
+impl Per100 {
+    /// Converts per serving values given the serving weight.
+    pub fn from_serving(serving: Per100, serving_weight: f32) -> Self {
+        serving.scaled(100.0 / serving_weight)
+    }
+
+    /// Nutrients of one serving of the given weight.
+    pub fn to_serving(self, serving_weight: f32) -> Per100 {
+        self.scaled(serving_weight / 100.0)
+    }
+
+    fn scaled(self, factor: f32) -> Per100 {
+        Per100 {
+            carbs: self.carbs * factor,
+            fats: self.fats * factor,
+            protein: self.protein * factor,
+            alcohol: self.alcohol * factor,
+            energy: self.energy.map(|energy| energy * factor),
+        }
+    }
+}
+
+/// Parses a logged amount, either a number of servings ("1.5") or a weight ("150g",
+/// "250 ml") which is converted to servings when the serving weight is known. Numbers are
+/// read with `parse_number`, e.g. that of the interface locale.
+pub fn parse_servings(
+    input: &str,
+    serving_weight: Option<f32>,
+    unit: ServingUnit,
+    parse_number: impl Fn(&str) -> Option<f32>,
+) -> Option<f32> {
+    let input = input.trim();
+    if let Some(servings) = parse_number(input) {
+        return Some(servings);
+    }
+
+    let weight = parse_number(input.strip_suffix(unit.suffix())?.trim())?;
+    let serving_weight = serving_weight.filter(|weight| *weight > 0.0)?;
+    Some(weight / serving_weight)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str, serving_weight: Option<f32>, unit: ServingUnit) -> Option<f32> {
+        parse_servings(input, serving_weight, unit, |number| number.parse().ok())
+    }
+
+    #[test]
+    fn plain_numbers_are_servings() {
+        assert_eq!(parse("1.5", Some(30.0), ServingUnit::Grams), Some(1.5));
+        assert_eq!(parse(" 2 ", None, ServingUnit::Grams), Some(2.0));
+    }
+
+    #[test]
+    fn weights_are_converted_to_servings() {
+        assert_eq!(parse("150g", Some(50.0), ServingUnit::Grams), Some(3.0));
+        assert_eq!(parse("150 g", Some(50.0), ServingUnit::Grams), Some(3.0));
+        assert_eq!(
+            parse("250 ml", Some(200.0), ServingUnit::Milliliters),
+            Some(1.25)
+        );
+    }
+
+    #[test]
+    fn weight_suffix_must_match_the_unit() {
+        assert_eq!(parse("250ml", Some(200.0), ServingUnit::Grams), None);
+        assert_eq!(parse("150g", Some(50.0), ServingUnit::Milliliters), None);
+    }
+
+    #[test]
+    fn weights_need_a_serving_weight() {
+        assert_eq!(parse("150g", None, ServingUnit::Grams), None);
+        assert_eq!(parse("150g", Some(0.0), ServingUnit::Grams), None);
+    }
+
+    #[test]
+    fn per_100_converts_to_and_from_servings() {
+        let per_100 = Per100 {
+            carbs: 60.0,
+            fats: 10.0,
+            protein: 20.0,
+            alcohol: 0.0,
+            energy: Some(410.0),
+        };
+        let serving = per_100.to_serving(50.0);
+        assert_eq!(serving.carbs, 30.0);
+        assert_eq!(serving.energy, Some(205.0));
+        assert_eq!(Per100::from_serving(serving, 50.0), per_100);
+    }
+}
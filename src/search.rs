@@ -0,0 +1,279 @@
+use rust_fuzzy_search::fuzzy_compare;
+use std::ops::Range;
+
+/// Weight of a token match in each searchable field of a food.
+const NAME_WEIGHT: f32 = 1.0;
+const ALIAS_WEIGHT: f32 = 0.9;
+const BRAND_WEIGHT: f32 = 0.6;
+
+/// Bonus when the whole query appears verbatim in the name.
+const PHRASE_BONUS: f32 = 0.3;
+
+/// Largest bonus given to the most logged food.
+const USAGE_BOOST: f32 = 0.3;
+
+/// Minimum fuzzy similarity for a misspelt token to count as a match.
+const FUZZY_THRESHOLD: f32 = 0.5;
+
+/// Minimum name similarity for two foods to be reported as likely duplicates.
+pub const DUPLICATE_THRESHOLD: f32 = 0.5;
+
+/// Shortest word stem that counts as shared between two names, e.g. "oat".
+const MIN_STEM_LEN: usize = 3;
+
+pub struct SearchMatch {
+    pub score: f32,
+    /// Character ranges of the name matched by the query, sorted and non-overlapping.
+    pub highlight: Vec<Range<usize>>,
+}
+
+/// Scores a food against `query`, matching each query word independently so word order
+/// does not matter.
+pub fn score(query: &str, name: &str, brand: &str, aliases: &[String]) -> SearchMatch {
+    let query_tokens = tokens(query);
+    if query_tokens.is_empty() {
+        return SearchMatch {
+            score: 0.0,
+            highlight: Vec::new(),
+        };
+    }
+
+    let name_tokens = tokens(name);
+    let brand_tokens = tokens(brand);
+    let alias_tokens: Vec<String> = aliases.iter().flat_map(|alias| tokens(alias)).collect();
+
+    let total: f32 = query_tokens
+        .iter()
+        .map(|query_token| {
+            let name = best_token_score(query_token, &name_tokens) * NAME_WEIGHT;
+            let alias = best_token_score(query_token, &alias_tokens) * ALIAS_WEIGHT;
+            let brand = best_token_score(query_token, &brand_tokens) * BRAND_WEIGHT;
+            name.max(alias).max(brand)
+        })
+        .sum();
+    let mut score = total / query_tokens.len() as f32;
+    if lowercase(name).contains(&lowercase(query.trim())) {
+        score += PHRASE_BONUS;
+    }
+
+    SearchMatch {
+        score,
+        highlight: highlight(&query_tokens, name),
+    }
+}
+
+/// Similarity of two food names between 0 and 1, ignoring case, punctuation and word order.
+///
+/// Besides the fuzzy similarity of the whole names, words sharing a stem count, so that
+/// "Oatmeal" and "Oats (rolled)" are similar although they are spelt quite differently.
+pub fn name_similarity(a: &str, b: &str) -> f32 {
+    let mut a = tokens(a);
+    let mut b = tokens(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.sort();
+    b.sort();
+    if a == b {
+        return 1.0;
+    }
+    let (shorter, longer) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    let shared_stems = shorter
+        .iter()
+        .filter(|token| longer.iter().any(|other| shares_stem(token, other)))
+        .count();
+    // Only when every word of the shorter name is found, "Chicken breast" and "Chicken thigh"
+    // share a word but are different foods.
+    let stem_similarity = if shared_stems == shorter.len() {
+        shared_stems as f32 / longer.len() as f32
+    } else {
+        0.0
+    };
+    fuzzy_compare(&a.join(" "), &b.join(" ")).max(stem_similarity)
+}
+
+/// Whether one word, without a plural "s", starts the other.
+fn shares_stem(a: &str, b: &str) -> bool {
+    let stem = |token: &str| -> String {
+        match token.strip_suffix('s') {
+            Some(stem) if stem.chars().count() >= MIN_STEM_LEN => stem.to_string(),
+            _ => token.to_string(),
+        }
+    };
+    let (a, b) = (stem(a), stem(b));
+    a.chars().count().min(b.chars().count()) >= MIN_STEM_LEN
+        && (a.starts_with(&b) || b.starts_with(&a))
+}
+
+/// Bonus for foods that are logged often, relative to the most logged food.
+pub fn usage_boost(count: u32, max_count: u32) -> f32 {
+    if max_count == 0 {
+        0.0
+    } else {
+        USAGE_BOOST * count as f32 / max_count as f32
+    }
+}
+
+fn best_token_score(query_token: &str, tokens: &[String]) -> f32 {
+    tokens
+        .iter()
+        .map(|token| token_score(query_token, token))
+        .fold(0.0, f32::max)
+}
+
+fn token_score(query_token: &str, token: &str) -> f32 {
+    if token == query_token {
+        1.0
+    } else if token.starts_with(query_token) {
+        0.9
+    } else if token.contains(query_token) {
+        0.7
+    } else {
+        let similarity = fuzzy_compare(query_token, token);
+        if similarity >= FUZZY_THRESHOLD {
+            similarity * 0.6
+        } else {
+            0.0
+        }
+    }
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    lowercase(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Lowercases character by character so character indices stay aligned with the input.
+fn lowercase(text: &str) -> String {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+fn highlight(query_tokens: &[String], name: &str) -> Vec<Range<usize>> {
+    let name: Vec<char> = lowercase(name).chars().collect();
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for query_token in query_tokens {
+        let query_token: Vec<char> = query_token.chars().collect();
+        if let Some(start) = name
+            .windows(query_token.len())
+            .position(|window| window == query_token.as_slice())
+        {
+            ranges.push(start..start + query_token.len());
+        }
+    }
+
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score_name(query: &str, name: &str) -> f32 {
+        score(query, name, "", &[]).score
+    }
+
+    #[test]
+    fn empty_query_matches_nothing() {
+        let search_match = score("  ", "Oatmeal", "", &[]);
+        assert_eq!(search_match.score, 0.0);
+        assert!(search_match.highlight.is_empty());
+    }
+
+    #[test]
+    fn word_order_does_not_matter() {
+        assert_eq!(
+            score_name("milk oat", "Oat Milk") + PHRASE_BONUS,
+            score_name("oat milk", "Oat Milk")
+        );
+        assert!(score_name("milk oat", "Oat Milk") >= 1.0);
+    }
+
+    #[test]
+    fn exact_tokens_rank_above_prefixes_and_substrings() {
+        let exact = score_name("oat", "Oat Bran");
+        let prefix = score_name("oat", "Oatmeal");
+        let substring = score_name("oat", "Goat Cheese");
+        assert!(exact > prefix, "{} > {}", exact, prefix);
+        assert!(prefix > substring, "{} > {}", prefix, substring);
+    }
+
+    #[test]
+    fn misspelt_tokens_still_match() {
+        assert!(score_name("bananna", "Banana") > 0.0);
+        assert_eq!(score_name("xyz", "Banana"), 0.0);
+    }
+
+    #[test]
+    fn aliases_rank_between_names_and_brands() {
+        let aliases = [String::from("Porridge")];
+        let by_name = score("porridge", "Porridge", "", &[]).score;
+        let by_alias = score("porridge", "Oatmeal", "", &aliases).score;
+        let by_brand = score("porridge", "Oatmeal", "Porridge Co", &[]).score;
+        assert!(by_name > by_alias, "{} > {}", by_name, by_alias);
+        assert!(by_alias > by_brand, "{} > {}", by_alias, by_brand);
+    }
+
+    #[test]
+    fn highlight_merges_overlapping_ranges() {
+        assert_eq!(score("oat meal", "Oatmeal", "", &[]).highlight, vec![0..7]);
+        assert_eq!(
+            score("milk oat", "Oat Milk", "", &[]).highlight,
+            vec![0..3, 4..8]
+        );
+    }
+
+    #[test]
+    fn highlight_keeps_character_indices_of_non_ascii_names() {
+        assert_eq!(
+            score("créme", "Crème créme", "", &[]).highlight,
+            vec![6..11]
+        );
+    }
+
+    #[test]
+    fn names_differing_in_case_and_word_order_are_duplicates() {
+        assert_eq!(name_similarity("Oatmeal", "oatmeal"), 1.0);
+        assert_eq!(name_similarity("Rolled oats", "Oats (rolled)"), 1.0);
+    }
+
+    #[test]
+    fn names_sharing_a_stem_are_duplicates() {
+        assert!(name_similarity("Oatmeal", "Oats (rolled)") >= DUPLICATE_THRESHOLD);
+        assert!(name_similarity("Oats", "Oatmeal") >= DUPLICATE_THRESHOLD);
+    }
+
+    #[test]
+    fn unrelated_names_are_not_duplicates() {
+        assert!(name_similarity("Banana", "Bread") < DUPLICATE_THRESHOLD);
+        assert!(name_similarity("Rice cakes with salt", "Rye bread") < DUPLICATE_THRESHOLD);
+        assert!(name_similarity("Chicken breast", "Chicken thigh") < DUPLICATE_THRESHOLD);
+        assert!(name_similarity("Whole milk", "Skim milk") < DUPLICATE_THRESHOLD);
+        assert!(name_similarity("Greek yogurt", "Greek salad") < DUPLICATE_THRESHOLD);
+        assert!(name_similarity("Brown rice", "White rice") < DUPLICATE_THRESHOLD);
+        assert_eq!(name_similarity("", "Bread"), 0.0);
+    }
+
+    #[test]
+    fn usage_boost_is_relative_to_the_most_logged_food() {
+        assert_eq!(usage_boost(0, 0), 0.0);
+        assert_eq!(usage_boost(5, 10), USAGE_BOOST / 2.0);
+        assert_eq!(usage_boost(10, 10), USAGE_BOOST);
+    }
+}
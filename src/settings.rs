@@ -0,0 +1,81 @@
+use crate::energy::{EnergyFactors, EnergyUnit};
+use crate::i18n::Locale;
+use crate::themes::ThemeSettings;
+use home::home_dir;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Contents of `settings.json`, which always lives in `~/.macros` so it can point the
+/// remaining data files elsewhere.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    pub window_width: u32,
+    pub window_height: u32,
+    pub text_size: f32,
+    /// Decimal places shown for macros and calories.
+    pub precision: usize,
+    /// Hour a diary day starts at, meals before it count toward the previous day.
+    pub day_start_hour: u32,
+    pub energy_unit: EnergyUnit,
+    pub energy_factors: EnergyFactors,
+    /// Language and number and date conventions, those of the system when unset.
+    pub locale: Option<Locale>,
+    /// Directory of foods, feed and the other data files, `~/.macros` when unset.
+    pub data_dir: Option<PathBuf>,
+    /// Directory exports are written to, the data directory when unset.
+    pub export_dir: Option<PathBuf>,
+    pub theme: ThemeSettings,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        UserSettings {
+            window_width: 1000,
+            window_height: 600,
+            text_size: 16.0,
+            precision: 1,
+            day_start_hour: 0,
+            energy_unit: EnergyUnit::default(),
+            energy_factors: EnergyFactors::default(),
+            locale: None,
+            data_dir: None,
+            export_dir: None,
+            theme: ThemeSettings::default(),
+        }
+    }
+}
+
+impl UserSettings {
+    pub fn load() -> Self {
+        let settings_dir = settings_dir();
+        fs::create_dir_all(&settings_dir).expect("Failed to create config directory");
+        let settings =
+            fs::read_to_string(settings_dir.join("settings.json")).unwrap_or(String::from("{}"));
+        serde_json::from_str(&settings).expect("Failed to parse settings.json")
+    }
+
+    pub fn save(&self) {
+        let settings_str =
+            serde_json::to_string_pretty(self).expect("Failed to serialize settings");
+        fs::write(settings_dir().join("settings.json"), settings_str)
+            .expect("Failed to write settings.json");
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale.unwrap_or_else(Locale::system)
+    }
+
+    pub fn data_dir(&self) -> PathBuf {
+        self.data_dir.clone().unwrap_or_else(settings_dir)
+    }
+
+    pub fn export_dir(&self) -> PathBuf {
+        self.export_dir.clone().unwrap_or_else(|| self.data_dir())
+    }
+}
+
+fn settings_dir() -> PathBuf {
+    home_dir().unwrap_or(PathBuf::from("")).join(".macros")
+}
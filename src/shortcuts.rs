@@ -0,0 +1,44 @@
+use crate::Message;
+use iced::event::Status;
+use iced::keyboard::{self, KeyCode};
+use iced::Event;
+
+/// Maps global key presses to messages.
+///
+/// Shortcuts use the platform command modifier (Ctrl, or Cmd on macOS) so they work while
+/// a text input has focus, even though a focused text input captures every key press.
+/// Escape is skipped once a widget captured it, e.g. to leave a focused text input, and
+/// everything else is left to widgets.
+pub fn keyboard_shortcut(event: Event, status: Status) -> Option<Message> {
+    let Event::Keyboard(keyboard::Event::KeyPressed {
+        key_code,
+        modifiers,
+    }) = event
+    else {
+        return None;
+    };
+
+    if key_code == KeyCode::Escape && status == Status::Ignored {
+        return Some(Message::Escape);
+    }
+    if !modifiers.command() {
+        return None;
+    }
+
+    match key_code {
+        KeyCode::K => Some(Message::TogglePalette),
+        KeyCode::F => Some(Message::FocusSearch),
+        KeyCode::E => Some(Message::EditTopSearchResult),
+        KeyCode::Z => Some(Message::Undo),
+        KeyCode::Key1 => Some(Message::ShortcutTab(0)),
+        KeyCode::Key2 => Some(Message::ShortcutTab(1)),
+        KeyCode::Key3 => Some(Message::ShortcutTab(2)),
+        KeyCode::Key4 => Some(Message::ShortcutTab(3)),
+        KeyCode::Key5 => Some(Message::ShortcutTab(4)),
+        KeyCode::Key6 => Some(Message::ShortcutTab(5)),
+        KeyCode::Key7 => Some(Message::ShortcutTab(6)),
+        KeyCode::Key8 => Some(Message::ShortcutTab(7)),
+        KeyCode::Key9 => Some(Message::ShortcutTab(8)),
+        _ => None,
+    }
+}
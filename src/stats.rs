@@ -0,0 +1,202 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Contents of `goals.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Goals {
+    /// Daily calorie goal, days are never within goal while unset.
+    pub calories: Option<f32>,
+    /// Allowed deviation from the calorie goal in percent.
+    pub tolerance: f32,
+}
+
+impl Default for Goals {
+    fn default() -> Self {
+        Goals {
+            calories: None,
+            tolerance: 10.0,
+        }
+    }
+}
+
+impl Goals {
+    pub fn is_within(&self, calories: f32) -> bool {
+        match self.calories {
+            Some(goal) => (calories - goal).abs() <= goal * self.tolerance / 100.0,
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Streak {
+    pub current: u32,
+    pub longest: u32,
+}
+
+/// Streaks over `dates`, which must be sorted and unique.
+///
+/// The current streak still counts when it ended yesterday, so it does not drop to zero
+/// just because nothing has been logged yet today.
+pub fn streak(dates: &[NaiveDate], today: NaiveDate) -> Streak {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &date in dates.iter().filter(|date| **date <= today) {
+        run = match previous {
+            Some(previous) if date - previous == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(date);
+    }
+
+    let current = match previous {
+        Some(last) if today - last <= Duration::days(1) => run,
+        _ => 0,
+    };
+    Streak { current, longest }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MonthAdherence {
+    /// First day of the month.
+    pub month: NaiveDate,
+    /// Days of the month up to today.
+    pub days: u32,
+    pub logged: u32,
+    pub within_goal: u32,
+}
+
+impl MonthAdherence {
+    pub fn logged_percent(&self) -> f32 {
+        self.logged as f32 * 100.0 / self.days as f32
+    }
+
+    pub fn within_goal_percent(&self) -> f32 {
+        self.within_goal as f32 * 100.0 / self.days as f32
+    }
+}
+
+/// Adherence of every month from the first logged day up to `today`, newest first.
+pub fn monthly_adherence(
+    logged: &[NaiveDate],
+    within_goal: &[NaiveDate],
+    today: NaiveDate,
+) -> Vec<MonthAdherence> {
+    let Some(&first) = logged.first() else {
+        return Vec::new();
+    };
+
+    let mut months: Vec<MonthAdherence> = Vec::new();
+    let mut month = first.with_day(1).unwrap();
+    while month <= today {
+        let next = if month.month() == 12 {
+            NaiveDate::from_ymd_opt(month.year() + 1, 1, 1).unwrap()
+        } else {
+            NaiveDate::from_ymd_opt(month.year(), month.month() + 1, 1).unwrap()
+        };
+        let end = (next - Duration::days(1)).min(today);
+        let in_month = |date: &&NaiveDate| **date >= month && **date <= end;
+        months.push(MonthAdherence {
+            month,
+            days: (end - month).num_days() as u32 + 1,
+            logged: logged.iter().filter(in_month).count() as u32,
+            within_goal: within_goal.iter().filter(in_month).count() as u32,
+        });
+        month = next;
+    }
+    months.reverse();
+    months
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn no_dates_have_no_streak() {
+        let streak = streak(&[], date(2024, 3, 10));
+        assert_eq!((streak.current, streak.longest), (0, 0));
+    }
+
+    #[test]
+    fn streak_ending_yesterday_is_current() {
+        let dates = [date(2024, 3, 7), date(2024, 3, 8), date(2024, 3, 9)];
+        let streak = streak(&dates, date(2024, 3, 10));
+        assert_eq!((streak.current, streak.longest), (3, 3));
+    }
+
+    #[test]
+    fn gap_ends_the_current_streak() {
+        let dates = [
+            date(2024, 3, 1),
+            date(2024, 3, 2),
+            date(2024, 3, 3),
+            date(2024, 3, 8),
+        ];
+        let streak = streak(&dates, date(2024, 3, 8));
+        assert_eq!((streak.current, streak.longest), (1, 3));
+
+        let streak = super::streak(&dates, date(2024, 3, 10));
+        assert_eq!((streak.current, streak.longest), (0, 3));
+    }
+
+    #[test]
+    fn streak_continues_across_month_boundaries() {
+        let dates = [date(2024, 2, 28), date(2024, 2, 29), date(2024, 3, 1)];
+        let streak = streak(&dates, date(2024, 3, 1));
+        assert_eq!((streak.current, streak.longest), (3, 3));
+    }
+
+    #[test]
+    fn future_dates_are_ignored() {
+        let dates = [date(2024, 3, 9), date(2024, 3, 10), date(2024, 3, 11)];
+        let streak = streak(&dates, date(2024, 3, 10));
+        assert_eq!((streak.current, streak.longest), (2, 2));
+    }
+
+    #[test]
+    fn goal_tolerance_is_a_percentage() {
+        let goals = Goals {
+            calories: Some(2000.0),
+            tolerance: 10.0,
+        };
+        assert!(goals.is_within(2200.0));
+        assert!(goals.is_within(1800.0));
+        assert!(!goals.is_within(2201.0));
+        assert!(!Goals::default().is_within(2000.0));
+    }
+
+    #[test]
+    fn no_logged_days_have_no_months() {
+        assert!(monthly_adherence(&[], &[], date(2024, 3, 10)).is_empty());
+    }
+
+    #[test]
+    fn months_run_from_the_first_logged_day_to_today() {
+        let logged = [date(2023, 12, 31), date(2024, 1, 1), date(2024, 2, 29)];
+        let within_goal = [date(2024, 1, 1)];
+        let months = monthly_adherence(&logged, &within_goal, date(2024, 3, 10));
+
+        let summary: Vec<(NaiveDate, u32, u32, u32)> = months
+            .iter()
+            .map(|month| (month.month, month.days, month.logged, month.within_goal))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (date(2024, 3, 1), 10, 0, 0),
+                (date(2024, 2, 1), 29, 1, 0),
+                (date(2024, 1, 1), 31, 1, 1),
+                (date(2023, 12, 1), 31, 1, 0),
+            ]
+        );
+        assert_eq!(months[3].logged_percent(), 100.0 / 31.0);
+    }
+}
@@ -0,0 +1,206 @@
+use chrono::{Duration, NaiveDate};
+use std::fmt;
+
+/// Number of days, ending yesterday, the estimate is fitted over.
+pub const WINDOW_DAYS: i64 = 28;
+
+/// Approximate energy stored in a kilogram of body weight.
+pub const KCAL_PER_KG: f32 = 7700.0;
+
+const MIN_LOGGED_DAYS: usize = 7;
+const MIN_WEIGH_INS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Confidence::Low => write!(f, "low"),
+            Confidence::Medium => write!(f, "medium"),
+            Confidence::High => write!(f, "high"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Estimate {
+    /// Estimated total daily energy expenditure in kcal.
+    pub tdee: f32,
+    /// One standard error of the estimate in kcal, derived from the weight regression.
+    pub error: f32,
+    pub confidence: Confidence,
+    pub logged_days: usize,
+    pub weigh_ins: usize,
+}
+
+impl Estimate {
+    /// Daily calories needed to change weight by `kg_per_week`.
+    pub fn goal_for_rate(&self, kg_per_week: f32) -> f32 {
+        self.tdee + kg_per_week * KCAL_PER_KG / 7.0
+    }
+}
+
+/// Fits maintenance calories over the window ending the day before `today`.
+///
+/// `intake` holds the calorie total of each logged day and `weights` one weigh-in per day.
+/// The weight slope comes from a least squares fit, so a single noisy weigh-in has
+/// limited influence. Returns `None` when there is not enough data in the window.
+pub fn estimate(
+    intake: &[(NaiveDate, f32)],
+    weights: &[(NaiveDate, f32)],
+    today: NaiveDate,
+) -> Option<Estimate> {
+    let end = today - Duration::days(1);
+    let start = today - Duration::days(WINDOW_DAYS);
+    let in_window = |date: &NaiveDate| *date >= start && *date <= end;
+
+    let intake: Vec<f32> = intake
+        .iter()
+        .filter(|(date, _)| in_window(date))
+        .map(|(_, calories)| *calories)
+        .collect();
+    let weights: Vec<(f32, f32)> = weights
+        .iter()
+        .filter(|(date, _)| in_window(date))
+        .map(|(date, weight)| ((*date - start).num_days() as f32, *weight))
+        .collect();
+
+    if intake.len() < MIN_LOGGED_DAYS || weights.len() < MIN_WEIGH_INS {
+        return None;
+    }
+
+    let n = weights.len() as f32;
+    let mean_x = weights.iter().map(|(x, _)| x).sum::<f32>() / n;
+    let mean_y = weights.iter().map(|(_, y)| y).sum::<f32>() / n;
+    let sxx: f32 = weights.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if sxx <= 0.0 {
+        return None;
+    }
+    let sxy: f32 = weights
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let slope = sxy / sxx;
+    let residuals: f32 = weights
+        .iter()
+        .map(|(x, y)| (y - (mean_y + slope * (x - mean_x))).powi(2))
+        .sum();
+    let slope_error = (residuals / (n - 2.0).max(1.0) / sxx).sqrt();
+
+    let mean_intake = intake.iter().sum::<f32>() / intake.len() as f32;
+    let coverage = intake.len() as f32 / WINDOW_DAYS as f32;
+    let confidence = if coverage >= 0.8 && weights.len() >= 12 {
+        Confidence::High
+    } else if coverage >= 0.5 && weights.len() >= 6 {
+        Confidence::Medium
+    } else {
+        Confidence::Low
+    };
+
+    Some(Estimate {
+        tdee: mean_intake - slope * KCAL_PER_KG,
+        error: slope_error * KCAL_PER_KG,
+        confidence,
+        logged_days: intake.len(),
+        weigh_ins: weights.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn days(
+        range: std::ops::RangeInclusive<u32>,
+        value: impl Fn(u32) -> f32,
+    ) -> Vec<(NaiveDate, f32)> {
+        range.map(|day| (date(day), value(day))).collect()
+    }
+
+    #[test]
+    fn no_data_gives_no_estimate() {
+        assert!(estimate(&[], &[], date(29)).is_none());
+    }
+
+    #[test]
+    fn too_few_logged_days_gives_no_estimate() {
+        let intake = days(1..=6, |_| 2000.0);
+        let weights = days(1..=28, |_| 80.0);
+        assert!(estimate(&intake, &weights, date(29)).is_none());
+    }
+
+    #[test]
+    fn weigh_ins_on_one_day_give_no_estimate() {
+        let intake = days(1..=28, |_| 2000.0);
+        let weights = vec![(date(10), 80.0), (date(10), 80.5), (date(10), 79.5)];
+        assert!(estimate(&intake, &weights, date(29)).is_none());
+    }
+
+    #[test]
+    fn data_outside_the_window_is_ignored() {
+        // Today and the days before the window don't count.
+        let intake = days(1..=29, |_| 2000.0);
+        let weights = days(1..=29, |_| 80.0);
+        let estimate = estimate(&intake, &weights, date(29)).unwrap();
+        assert_eq!(estimate.logged_days, 28);
+        assert_eq!(estimate.weigh_ins, 28);
+
+        let estimate = super::estimate(&intake, &weights, date(31)).unwrap();
+        assert_eq!(estimate.logged_days, 27);
+    }
+
+    #[test]
+    fn stable_weight_means_intake_is_maintenance() {
+        let intake = days(1..=28, |_| 2500.0);
+        let weights = days(1..=28, |_| 80.0);
+        let estimate = estimate(&intake, &weights, date(29)).unwrap();
+        assert!((estimate.tdee - 2500.0).abs() < 1.0);
+        assert!(estimate.error.abs() < 1.0);
+        assert_eq!(estimate.confidence, Confidence::High);
+    }
+
+    #[test]
+    fn losing_weight_means_maintenance_is_above_intake() {
+        // 0.1 kg a day is 770 kcal below maintenance.
+        let intake = days(1..=28, |_| 2000.0);
+        let weights = days(1..=28, |day| 90.0 - 0.1 * day as f32);
+        let estimate = estimate(&intake, &weights, date(29)).unwrap();
+        assert!((estimate.tdee - 2770.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn gaps_lower_the_confidence() {
+        let intake: Vec<(NaiveDate, f32)> =
+            days(1..=28, |_| 2000.0).into_iter().step_by(3).collect();
+        let weights = days(1..=28, |_| 80.0)
+            .into_iter()
+            .step_by(7)
+            .collect::<Vec<_>>();
+        let estimate = estimate(&intake, &weights, date(29)).unwrap();
+        assert_eq!(estimate.logged_days, 10);
+        assert_eq!(estimate.weigh_ins, 4);
+        assert_eq!(estimate.confidence, Confidence::Low);
+    }
+
+    #[test]
+    fn goal_for_rate_adds_the_energy_of_the_weight_change() {
+        let estimate = Estimate {
+            tdee: 2500.0,
+            error: 0.0,
+            confidence: Confidence::High,
+            logged_days: 28,
+            weigh_ins: 28,
+        };
+        assert_eq!(estimate.goal_for_rate(0.0), 2500.0);
+        assert_eq!(estimate.goal_for_rate(-0.7), 1730.0);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::i18n::Catalog;
+use crate::QuickAdd;
+use serde::{Deserialize, Serialize};
+
+/// A named group of feed entries that can be logged in one action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealTemplate {
+    pub id: u32,
+    pub name: String,
+    pub items: Vec<TemplateItem>,
+
+    #[serde(skip_serializing, skip_deserializing)]
+    pub scale: String,
+}
+
+/// Same shape as a feed entry minus the date, either a food or quick added macros.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateItem {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub food_id: Option<u32>,
+    pub amount: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quick_add: Option<QuickAdd>,
+
+    #[serde(skip_serializing, skip_deserializing)]
+    pub amount_input: String,
+}
+
+impl MealTemplate {
+    /// Resets the edit buffers, which are not persisted.
+    pub fn reset_inputs(&mut self, i18n: &Catalog) {
+        self.scale = i18n.input(1.0);
+        self.items
+            .iter_mut()
+            .for_each(|item| item.amount_input = i18n.input(item.amount));
+    }
+}
@@ -0,0 +1,162 @@
+use iced::theme::{self, Palette};
+use iced::widget::text_input;
+use iced::{Color, Theme};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Accent colors offered besides a custom hex color.
+pub const ACCENT_PRESETS: [(&str, &str); 5] = [
+    ("Blue", "#3b82f6"),
+    ("Green", "#16a34a"),
+    ("Purple", "#8b5cf6"),
+    ("Orange", "#ea580c"),
+    ("Pink", "#db2777"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    Light,
+    Dark,
+    /// Follows the light or dark mode of the operating system.
+    #[default]
+    System,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 3] = [ThemeChoice::Light, ThemeChoice::Dark, ThemeChoice::System];
+}
+
+impl fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeChoice::Light => write!(f, "Light"),
+            ThemeChoice::Dark => write!(f, "Dark"),
+            ThemeChoice::System => write!(f, "System"),
+        }
+    }
+}
+
+/// Theme part of `settings.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeSettings {
+    pub choice: ThemeChoice,
+    /// Hex color replacing the primary color of the palette, e.g. `#3b82f6`.
+    pub accent: Option<String>,
+}
+
+impl ThemeSettings {
+    /// Builds the theme, asking the operating system for its mode when following it.
+    pub fn theme(&self) -> Theme {
+        let dark = match self.choice {
+            ThemeChoice::Light => false,
+            ThemeChoice::Dark => true,
+            ThemeChoice::System => dark_light::detect() == dark_light::Mode::Dark,
+        };
+        let mut palette = if dark { Palette::DARK } else { Palette::LIGHT };
+        if let Some(accent) = self.accent.as_deref().and_then(parse_hex) {
+            palette.primary = accent;
+        }
+        Theme::custom(palette)
+    }
+}
+
+/// Parses `#rrggbb` or `rrggbb`.
+pub fn parse_hex(hex: &str) -> Option<Color> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |range| u8::from_str_radix(&hex[range], 16).ok();
+    Some(Color::from_rgb8(
+        channel(0..2)?,
+        channel(2..4)?,
+        channel(4..6)?,
+    ))
+}
+
+/// Background of the row at `index` in a striped list.
+pub fn stripe(theme: &Theme, index: usize) -> Color {
+    let background = &theme.extended_palette().background;
+    if index.is_multiple_of(2) {
+        background.weak.color
+    } else {
+        mix(background.base.color, background.weak.color, 0.5)
+    }
+}
+
+/// Color of secondary text such as brands and hints.
+pub fn muted(theme: &Theme) -> Color {
+    let palette = theme.palette();
+    mix(palette.text, palette.background, 0.5)
+}
+
+/// Color of warnings that don't block anything, a softer take on the danger color.
+pub fn warning(theme: &Theme) -> Color {
+    mix(theme.palette().danger, muted(theme), 0.4)
+}
+
+fn mix(a: Color, b: Color, factor: f32) -> Color {
+    Color::new(
+        a.r + (b.r - a.r) * factor,
+        a.g + (b.g - a.g) * factor,
+        a.b + (b.b - a.b) * factor,
+        a.a + (b.a - a.a) * factor,
+    )
+}
+
+/// Text input style marking a value that failed validation.
+pub fn invalid_input() -> theme::TextInput {
+    theme::TextInput::Custom(Box::new(InvalidInput))
+}
+
+struct InvalidInput;
+
+impl InvalidInput {
+    fn with_danger_border(
+        theme: &Theme,
+        appearance: text_input::Appearance,
+    ) -> text_input::Appearance {
+        text_input::Appearance {
+            border_color: theme.palette().danger,
+            border_width: 2.0,
+            ..appearance
+        }
+    }
+}
+
+impl text_input::StyleSheet for InvalidInput {
+    type Style = Theme;
+
+    fn active(&self, theme: &Theme) -> text_input::Appearance {
+        Self::with_danger_border(theme, theme.active(&theme::TextInput::Default))
+    }
+
+    fn focused(&self, theme: &Theme) -> text_input::Appearance {
+        Self::with_danger_border(theme, theme.focused(&theme::TextInput::Default))
+    }
+
+    fn hovered(&self, theme: &Theme) -> text_input::Appearance {
+        Self::with_danger_border(theme, theme.hovered(&theme::TextInput::Default))
+    }
+
+    fn placeholder_color(&self, theme: &Theme) -> Color {
+        theme.placeholder_color(&theme::TextInput::Default)
+    }
+
+    fn value_color(&self, theme: &Theme) -> Color {
+        theme.value_color(&theme::TextInput::Default)
+    }
+
+    fn disabled_color(&self, theme: &Theme) -> Color {
+        theme.disabled_color(&theme::TextInput::Default)
+    }
+
+    fn selection_color(&self, theme: &Theme) -> Color {
+        theme.selection_color(&theme::TextInput::Default)
+    }
+
+    fn disabled(&self, theme: &Theme) -> text_input::Appearance {
+        theme.disabled(&theme::TextInput::Default)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::calendar::Recorded;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Volumes in ml offered as one click buttons on the feed.
+pub const QUICK_ADD_VOLUMES: [f32; 3] = [250.0, 500.0, 750.0];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaterEntry {
+    pub id: u32,
+    /// Volume in ml.
+    pub amount: f32,
+    pub date: DateTime<Utc>,
+    /// Seconds east of UTC where the entry was logged, filled in for older entries on load.
+    #[serde(default)]
+    pub utc_offset: Option<i32>,
+}
+
+impl Recorded for WaterEntry {
+    fn date(&self) -> DateTime<Utc> {
+        self.date
+    }
+
+    fn utc_offset(&self) -> Option<i32> {
+        self.utc_offset
+    }
+
+    fn utc_offset_mut(&mut self) -> &mut Option<i32> {
+        &mut self.utc_offset
+    }
+}
+
+/// Contents of `water.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WaterLog {
+    /// Daily hydration target in ml.
+    pub target: f32,
+    pub entries: Vec<WaterEntry>,
+}
+
+impl Default for WaterLog {
+    fn default() -> Self {
+        WaterLog {
+            target: 2000.0,
+            entries: Vec::new(),
+        }
+    }
+}
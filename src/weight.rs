@@ -0,0 +1,130 @@
+use crate::calendar::Recorded;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const POUNDS_PER_KILOGRAM: f32 = 2.204_622_6;
+
+/// Fraction of the gap between the trend and a new weigh-in that is closed per day.
+const TREND_SMOOTHING: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WeightUnit {
+    #[default]
+    Kg,
+    Lb,
+}
+
+impl WeightUnit {
+    pub const ALL: [WeightUnit; 2] = [WeightUnit::Kg, WeightUnit::Lb];
+
+    pub fn in_unit(self, kg: f32) -> f32 {
+        match self {
+            WeightUnit::Kg => kg,
+            WeightUnit::Lb => kg * POUNDS_PER_KILOGRAM,
+        }
+    }
+
+    pub fn to_kg(self, value: f32) -> f32 {
+        match self {
+            WeightUnit::Kg => value,
+            WeightUnit::Lb => value / POUNDS_PER_KILOGRAM,
+        }
+    }
+}
+
+impl fmt::Display for WeightUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeightUnit::Kg => write!(f, "kg"),
+            WeightUnit::Lb => write!(f, "lb"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightEntry {
+    pub id: u32,
+    /// Always stored in kilograms regardless of the display unit.
+    pub weight: f32,
+    pub date: DateTime<Utc>,
+    /// Seconds east of UTC where the entry was logged, filled in for older entries on load.
+    #[serde(default)]
+    pub utc_offset: Option<i32>,
+}
+
+impl Recorded for WeightEntry {
+    fn date(&self) -> DateTime<Utc> {
+        self.date
+    }
+
+    fn utc_offset(&self) -> Option<i32> {
+        self.utc_offset
+    }
+
+    fn utc_offset_mut(&mut self) -> &mut Option<i32> {
+        &mut self.utc_offset
+    }
+}
+
+/// Contents of `weight.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeightLog {
+    pub unit: WeightUnit,
+    pub entries: Vec<WeightEntry>,
+    /// Desired weight change in kg per week, used to suggest a calorie goal.
+    pub target_rate: Option<f32>,
+}
+
+impl WeightLog {
+    /// Exponentially smoothed weight for each entry, entries must be sorted by date.
+    pub fn trend(&self) -> Vec<f32> {
+        let mut trend: Vec<f32> = Vec::with_capacity(self.entries.len());
+        let mut previous: Option<(f32, DateTime<Utc>)> = None;
+        for entry in self.entries.iter() {
+            let value = match previous {
+                None => entry.weight,
+                Some((last, last_date)) => {
+                    let days = (entry.date - last_date).num_seconds().max(0) as f32 / 86400.0;
+                    let alpha = 1.0 - (1.0 - TREND_SMOOTHING).powf(days.max(1.0 / 24.0));
+                    last + alpha * (entry.weight - last)
+                }
+            };
+            trend.push(value);
+            previous = Some((value, entry.date));
+        }
+        trend
+    }
+
+    /// Last weigh-in of each diary day, oldest first.
+    pub fn daily_weights(&self, day_start_hour: u32) -> Vec<(NaiveDate, f32)> {
+        let mut days: Vec<(NaiveDate, f32)> = Vec::new();
+        for entry in self.entries.iter() {
+            let day = entry.diary_date(day_start_hour);
+            match days.last_mut() {
+                Some(last) if last.0 == day => last.1 = entry.weight,
+                _ => days.push((day, entry.weight)),
+            }
+        }
+        days
+    }
+
+    /// Trend weight at the end of each week (starting Monday) that has weigh-ins, oldest first.
+    pub fn weekly_trend(&self, day_start_hour: u32) -> Vec<(NaiveDate, f32)> {
+        let mut weeks: Vec<(NaiveDate, f32)> = Vec::new();
+        for (entry, trend) in self.entries.iter().zip(self.trend()) {
+            let week = week_start(entry.diary_date(day_start_hour));
+            match weeks.last_mut() {
+                Some(last) if last.0 == week => last.1 = trend,
+                _ => weeks.push((week, trend)),
+            }
+        }
+        weeks
+    }
+}
+
+/// Monday of the week containing `date`.
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}